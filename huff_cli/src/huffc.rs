@@ -13,14 +13,19 @@ use ethers_core::utils::hex;
 use huff_codegen::Codegen;
 use huff_core::Compiler;
 use huff_tests::{
-    prelude::{print_test_report, ReportKind},
+    prelude::{
+        compare_snapshot, print_coverage_report, print_snapshot_report, print_test_list,
+        print_test_report, read_reference_bytecode, read_snapshot, write_lcov, write_snapshot,
+        write_test_report, CoverageReport, Debugger, FuzzConfig, ReportKind, TestFilter,
+        TestStatus, LCOV_FILE, SNAPSHOT_FILE,
+    },
     HuffTester,
 };
 use huff_utils::{
     file_provider::FileSystemFileProvider,
     prelude::{
         export_interfaces, gen_sol_interfaces, str_to_bytes32, unpack_files, AstSpan, BytecodeRes,
-        CodegenError, CodegenErrorKind, CompilerError, EVMVersion, FileSource, Literal,
+        CodegenError, CodegenErrorKind, CompilerError, Contract, EVMVersion, FileSource, Literal,
         OutputLocation, Span,
     },
 };
@@ -88,7 +93,8 @@ struct Huff {
     #[clap(short = 'l', long = "label-indices")]
     label_indices: bool,
 
-    /// Override / set constants for the compilation environment.
+    /// Override / set constants for the compilation environment. Overridden constants are
+    /// defined for `#ifdef` and `#ifndef` directives.
     #[clap(short = 'c', long = "constants", multiple_values = true)]
     constants: Option<Vec<String>>,
 
@@ -104,22 +110,99 @@ struct Huff {
     #[clap(short = 'e', long = "evm-version")]
     evm_version: Option<String>,
 
-    /// Test subcommand
+    /// Subcommands
     #[clap(subcommand)]
-    test: Option<TestCommands>,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand, Clone, Debug)]
-enum TestCommands {
+enum Commands {
     /// Test subcommand
     Test {
-        /// Format the test output as a list, table, or JSON.
+        /// Format the test output as a list, table, JSON, JUnit XML, or TAP.
         #[clap(short = 'f', long = "format")]
         format: Option<String>,
 
-        /// Match a specific test
+        /// Write the JSON, JUnit XML, or TAP test report to a file, printing a list of the
+        /// results instead.
+        #[clap(short = 'o', long = "out")]
+        out: Option<String>,
+
+        /// Only run tests whose name matches a glob or regex
         #[clap(short = 'm', long = "match")]
         match_: Option<String>,
+
+        /// Only run tests defined in files whose path matches a glob or regex
+        #[clap(long = "match-path")]
+        match_path: Option<String>,
+
+        /// Skip tests whose name or file path matches a glob or regex
+        #[clap(long = "skip")]
+        skip: Option<String>,
+
+        /// List the selected tests of each file without running them
+        #[clap(long = "list")]
+        list: bool,
+
+        /// Trace verbosity. `-vvv` prints execution traces for failing tests, `-vvvv` for all
+        /// tests.
+        #[clap(short = 'v', long = "verbosity", parse(from_occurrences))]
+        verbosity: u8,
+
+        /// Write the gas consumed by each test to a `.gas-snapshot` file.
+        #[clap(long = "snapshot")]
+        snapshot: bool,
+
+        /// Compare the gas consumed by each test against the `.gas-snapshot` file, failing on
+        /// regressions.
+        #[clap(long = "check-snapshot")]
+        check_snapshot: bool,
+
+        /// The gas regression tolerance, as a percentage, when checking a gas snapshot.
+        #[clap(long = "tolerance", default_value = "0")]
+        tolerance: f64,
+
+        /// Record the code coverage of the tests, writing an `lcov.info` file and printing a
+        /// summary.
+        #[clap(long = "coverage")]
+        coverage: bool,
+
+        /// The number of threads to run tests on. Defaults to the number of available cores.
+        #[clap(short = 'j', long = "jobs")]
+        jobs: Option<usize>,
+
+        /// Run the invariant tests against random sequences of calls to the contract's
+        /// functions, instead of running the tests.
+        #[clap(long = "invariant")]
+        invariant: bool,
+
+        /// Compare the contract's functions against a reference implementation, given as an
+        /// artifact JSON or a file of hex encoded creation bytecode, instead of running the tests.
+        #[clap(long = "diff", conflicts_with = "invariant")]
+        diff: Option<String>,
+
+        /// Step through the execution of a single test in an interactive debugger.
+        #[clap(long = "debug", conflicts_with_all = &["list", "invariant", "diff"])]
+        debug: Option<String>,
+
+        /// The number of call sequences of an invariant or differential campaign.
+        #[clap(long = "runs", default_value = "256")]
+        runs: usize,
+
+        /// The number of calls in each sequence of an invariant or differential campaign.
+        #[clap(long = "depth", default_value = "15")]
+        depth: usize,
+
+        /// The seed of the random calls of an invariant or differential campaign.
+        #[clap(long = "seed", default_value = "0")]
+        seed: u64,
+    },
+    /// Print a table of the function signatures, selectors, mutability and the labels they're
+    /// dispatched to
+    Selectors {
+        /// The contract(s) to print the selectors of, in place of the path before the
+        /// subcommand.
+        path: Option<String>,
     },
 }
 
@@ -135,6 +218,43 @@ pub(crate) fn get_input(prompt: &str) -> String {
     input.trim().to_string()
 }
 
+/// Prints a table of a contract's function signatures, selectors, mutability, the labels they're
+/// dispatched to and the gas a `__DISPATCH` dispatcher spends on them
+fn print_selectors(evm_version: &EVMVersion, contract: &Contract) {
+    if contract.functions.is_empty() {
+        println!("{}", Paint::yellow("No functions defined."));
+        return
+    }
+
+    let dispatches = contract.dispatch_labels();
+    let gas = Codegen::dispatch_gas(evm_version, contract);
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+    table
+        .set_header(vec![
+            Cell::new("Function Signature").fg(Color::Cyan),
+            Cell::new("Selector").fg(Color::Cyan),
+            Cell::new("Mutability").fg(Color::Cyan),
+            Cell::new("Dispatched To").fg(Color::Cyan),
+            Cell::new("Dispatch Gas").fg(Color::Cyan),
+        ])
+        .add_rows(contract.functions.iter().map(|f| {
+            // A function that's never dispatched to can't be called
+            let labels = match dispatches.get(&f.signature) {
+                Some(labels) => Cell::new(labels.join(", ")),
+                None => Cell::new("-").fg(Color::Yellow),
+            };
+            Row::from(vec![
+                Cell::new(f.canonical_signature()),
+                Cell::new(format!("0x{}", hex::encode(f.signature))),
+                Cell::new(&f.fn_type),
+                labels,
+                gas.get(&f.signature).map_or(Cell::new("-"), Cell::new),
+            ])
+        }));
+    println!("{table}");
+}
+
 fn main() {
     // Into App
     let mut app: App = Huff::into_app();
@@ -147,6 +267,11 @@ fn main() {
         Compiler::init_tracing_subscriber(Some(vec![tracing::Level::DEBUG.into()]));
     }
 
+    // The selectors subcommand may be given the path after it
+    if let Some(Commands::Selectors { path: Some(path) }) = &cli.command {
+        cli.path = Some(path.clone());
+    }
+
     // Check if no argument is provided
     if cli.path.is_none() {
         // Print help and exit
@@ -171,10 +296,11 @@ fn main() {
                 let parts = c.as_str().split('=').collect::<Vec<_>>();
 
                 // Check that constant override argument is valid
-                // Key rule: Alphabetic chars + underscore
+                // Key rule: Alphanumeric chars + underscore, not starting with a digit
                 // Value rule: Valid literal string (0x...)
                 if parts.len() != 2 ||
-                    parts[0].chars().any(|c| !(c.is_alphabetic() || c == '_')) ||
+                    parts[0].chars().any(|c| !(c.is_alphanumeric() || c == '_')) ||
+                    parts[0].starts_with(|c: char| c.is_numeric()) ||
                     !parts[1].starts_with("0x") ||
                     parts[1][2..].chars().any(|c| {
                         !(c.is_numeric() ||
@@ -223,6 +349,7 @@ fn main() {
         bytecode: cli.bytecode,
         cached: use_cache,
         file_provider: Arc::new(FileSystemFileProvider {}),
+        warnings: Default::default(),
     };
 
     if cli.label_indices {
@@ -306,18 +433,154 @@ fn main() {
         return;
     }
 
-    if let Some(TestCommands::Test { format, match_ }) = cli.test {
+    if let Some(Commands::Selectors { .. }) = cli.command {
+        match compiler.grab_contracts() {
+            Ok(contracts) => {
+                for (source, contract) in sources.iter().zip(&contracts) {
+                    if contracts.len() > 1 {
+                        println!("{}", Paint::blue(source));
+                    }
+                    print_selectors(&evm_version, contract);
+                }
+            }
+            Err(e) => {
+                tracing::error!(target: "cli", "PARSER ERRORED!");
+                eprintln!("{}", Paint::red(e));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Commands::Test {
+        format,
+        out,
+        match_,
+        match_path,
+        skip,
+        list,
+        verbosity,
+        snapshot,
+        check_snapshot,
+        tolerance,
+        coverage,
+        jobs,
+        invariant,
+        diff,
+        debug,
+        runs,
+        depth,
+        seed,
+    }) = cli.command
+    {
         match compiler.grab_contracts() {
             Ok(contracts) => {
-                let match_ = Rc::new(match_);
+                let filter = match TestFilter::new(
+                    match_.as_deref(),
+                    match_path.as_deref(),
+                    skip.as_deref(),
+                ) {
+                    Ok(filter) => Rc::new(filter),
+                    Err(e) => {
+                        eprintln!("{}", Paint::red(e));
+                        std::process::exit(1);
+                    }
+                };
+
+                if list {
+                    let mut tests: BTreeMap<String, Vec<String>> = BTreeMap::new();
+                    for contract in &contracts {
+                        for (path, names) in HuffTester::new(contract, Rc::clone(&filter)).list() {
+                            let file_tests = tests.entry(path).or_default();
+                            // Files imported by multiple contracts are only listed once
+                            names.into_iter().for_each(|n| {
+                                if !file_tests.contains(&n) {
+                                    file_tests.push(n)
+                                }
+                            });
+                        }
+                    }
+                    print_test_list(&tests);
+                    return;
+                }
+
+                if let Some(name) = debug {
+                    let tester = contracts
+                        .iter()
+                        .map(|contract| HuffTester::new(contract, Rc::clone(&filter)))
+                        .find(|tester| tester.macros.iter().any(|m| m.name == name));
+                    let Some(mut tester) = tester else {
+                        eprintln!("{}", Paint::red(format!("No test named \"{name}\" found.")));
+                        std::process::exit(1);
+                    };
+                    tester.runner.evm_version = evm_version.clone();
+
+                    let res = match tester.debug(&name) {
+                        Ok(res) => res,
+                        Err(e) => {
+                            eprintln!("{}", Paint::red(e));
+                            std::process::exit(1);
+                        }
+                    };
+                    if let Err(e) = Debugger::new(res).run(&mut std::io::stdin().lock()) {
+                        eprintln!("{}", Paint::red(e));
+                        std::process::exit(1);
+                    }
+                    return;
+                }
+
+                let mut results = Vec::new();
+
+                // When writing the report to a file, a list of the results is still printed
+                let report_kind = match ReportKind::try_from(&format) {
+                    Ok(report_kind) => report_kind,
+                    Err(e) => {
+                        eprintln!("{}", Paint::red(e));
+                        std::process::exit(1);
+                    }
+                };
+                if out.is_some() && !report_kind.is_machine_readable() {
+                    eprintln!(
+                        "{}",
+                        Paint::red(
+                            "Only JSON, JUnit and TAP test reports can be written to a file"
+                        )
+                    );
+                    std::process::exit(1);
+                }
+                let print_kind = if out.is_some() { ReportKind::List } else { report_kind };
+                let fuzz_config = FuzzConfig { runs, depth, seed };
+                let reference = match diff.as_deref().map(read_reference_bytecode).transpose() {
+                    Ok(reference) => reference,
+                    Err(e) => {
+                        eprintln!("{}", Paint::red(e));
+                        std::process::exit(1);
+                    }
+                };
+                let suite_start = Instant::now();
 
                 for contract in &contracts {
-                    let tester = HuffTester::new(contract, Rc::clone(&match_));
+                    let mut tester = HuffTester::new(contract, Rc::clone(&filter));
+                    tester.runner.verbosity = verbosity;
+                    tester.runner.coverage = coverage;
+                    tester.runner.evm_version = evm_version.clone();
+                    tester.jobs = jobs;
 
                     let start = Instant::now();
-                    match tester.execute() {
+                    let res = if invariant {
+                        tester.execute_invariants(&fuzz_config)
+                    } else if let Some(reference) = &reference {
+                        tester.execute_differential(reference, &fuzz_config)
+                    } else {
+                        tester.execute()
+                    };
+                    match res {
+                        // Machine readable reports cover every contract, so they are printed
+                        // once all of them have been tested
+                        Ok(res) if print_kind.is_machine_readable() => results.extend(res),
                         Ok(res) => {
-                            print_test_report(res, ReportKind::from(&format), start);
+                            results.extend(res.iter().cloned());
+                            print_test_report(res, print_kind, start);
                         }
                         Err(e) => {
                             eprintln!("{}", Paint::red(e));
@@ -325,6 +588,59 @@ fn main() {
                         }
                     };
                 }
+
+                if print_kind.is_machine_readable() {
+                    print_test_report(results.clone(), print_kind, suite_start);
+                }
+
+                if let Some(out) = out {
+                    if let Err(e) =
+                        write_test_report(&results, report_kind, suite_start.elapsed(), &out)
+                    {
+                        eprintln!("{}", Paint::red(e));
+                        std::process::exit(1);
+                    }
+                    println!("➜ Wrote test report to {}", Paint::blue(out));
+                }
+
+                if coverage {
+                    let report = CoverageReport::new(&contracts, &results);
+                    print_coverage_report(&report);
+                    if let Err(e) = write_lcov(&report, LCOV_FILE) {
+                        eprintln!("{}", Paint::red(e));
+                        std::process::exit(1);
+                    }
+                    println!("➜ Wrote coverage report to {}", Paint::blue(LCOV_FILE));
+                }
+
+                if check_snapshot {
+                    let regressed = match read_snapshot(SNAPSHOT_FILE) {
+                        Ok(gas_snapshot) => print_snapshot_report(
+                            &compare_snapshot(&results, &gas_snapshot),
+                            tolerance,
+                        ),
+                        Err(e) => {
+                            eprintln!("{}", Paint::red(e));
+                            std::process::exit(1);
+                        }
+                    };
+                    if regressed {
+                        std::process::exit(1);
+                    }
+                }
+
+                if snapshot {
+                    if let Err(e) = write_snapshot(&results, SNAPSHOT_FILE) {
+                        eprintln!("{}", Paint::red(e));
+                        std::process::exit(1);
+                    }
+                    println!("➜ Wrote gas snapshot to {}", Paint::blue(SNAPSHOT_FILE));
+                }
+
+                // Any failed test fails the command, once the reports have been written
+                if results.iter().any(|r| !matches!(r.status, TestStatus::Success)) {
+                    std::process::exit(1);
+                }
             }
             Err(e) => {
                 tracing::error!(target: "cli", "PARSER ERRORED!");
@@ -350,6 +666,11 @@ fn main() {
         sp.stop();
         println!(" ");
     }
+
+    // Warnings don't fail the compilation
+    for warning in compiler.warnings.lock().unwrap().iter() {
+        eprintln!("{}", Paint::yellow(format!("{warning}")));
+    }
     match compile_res {
         Ok(mut artifacts) => {
             if artifacts.is_empty() {
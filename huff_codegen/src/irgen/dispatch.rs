@@ -0,0 +1,380 @@
+use huff_utils::prelude::*;
+
+/// The most slots a packed jump table dispatcher may index its selectors into
+pub const MAX_DISPATCH_SLOTS: usize = 1024;
+
+/// The most selectors a binary search dispatcher compares one by one
+const LEAF_SELECTORS: usize = 3;
+
+/// The strategy a `__DISPATCH` dispatcher finds the label of a selector with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchKind {
+    /// Compares the selector against each function's, in definition order
+    Linear,
+    /// Binary searches the sorted selectors, comparing the last few one by one
+    Binary,
+    /// Jumps through a packed jump table, indexed by the selector modulo the smallest number of
+    /// slots that tells the selectors apart
+    PackedJumpTable,
+}
+
+impl TryFrom<&str> for DispatchKind {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "linear" => Ok(DispatchKind::Linear),
+            "binary" => Ok(DispatchKind::Binary),
+            "packed_jumptable" => Ok(DispatchKind::PackedJumpTable),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A step of a dispatcher. The selector is the top stack item before and after every step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    /// `0x00 calldataload 0xe0 shr`
+    Load,
+    /// `dup1 <selector> eq <label> jumpi`
+    Eq([u8; 4], String),
+    /// `dup1 <pivot> gt <destination> jumpi`, jumping if the selector is below the pivot
+    Lt([u8; 4], usize),
+    /// `<destination> jump`
+    Jump(usize),
+    /// A `jumpdest` of the dispatcher
+    Dest(usize),
+    /// Jumps to the destination in the slot of the selector modulo the number of slots, read from
+    /// a packed jump table appended to the code
+    Lookup(Vec<usize>),
+}
+
+/// A piece of the bytecode of a dispatcher
+enum Piece {
+    /// Raw bytecode
+    Code(String),
+    /// A push of a contract label
+    Label(String),
+    /// A push of a dispatcher destination
+    Push(usize),
+    /// A dispatcher destination
+    Dest(usize),
+    /// A push of the dispatcher's jump table start
+    Table,
+}
+
+/// A selector dispatcher generated by the `__DISPATCH` builtin.
+///
+/// The dispatcher loads the selector from calldata and jumps to the label of its function,
+/// leaving the selector on the stack. A selector that matches no function falls through the
+/// dispatcher, with the selector on the stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dispatcher {
+    /// The dispatch strategy
+    pub kind: DispatchKind,
+    /// The selector and label of each dispatched function, in definition order
+    pub targets: Vec<([u8; 4], String)>,
+    /// The steps of the dispatcher, in bytecode order
+    steps: Vec<Step>,
+}
+
+impl Dispatcher {
+    /// Generates the dispatcher of a `__DISPATCH` invocation from the contract's functions
+    pub fn new(contract: &Contract, bf: &BuiltinFunctionCall) -> Result<Self, CodegenError> {
+        let error = |message: String| {
+            tracing::error!(target: "codegen", "INVALID __DISPATCH INVOCATION: {}", message);
+            CodegenError {
+                kind: CodegenErrorKind::InvalidArguments(message),
+                span: bf.span.clone(),
+                token: None,
+            }
+        };
+
+        let kind = match bf.args.as_slice() {
+            [kind] | [kind, _] => kind.name.as_deref().and_then(|k| DispatchKind::try_from(k).ok()),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            error(String::from(
+                "Invalid arguments passed to __DISPATCH, should be \"linear\", \"binary\" or \"packed_jumptable\" and an optional jump table",
+            ))
+        })?;
+        let targets = contract.dispatch_targets(bf).ok_or_else(|| {
+            error(format!(
+                "The jump table passed to __DISPATCH should hold a label for each of the {} functions",
+                contract.functions.len()
+            ))
+        })?;
+
+        // Destination 0 is the end of the dispatcher
+        let mut steps = vec![Step::Load];
+        let mut dests = 0;
+        match kind {
+            DispatchKind::Linear => {
+                steps.extend(targets.iter().map(|(s, l)| Step::Eq(*s, l.clone())));
+            }
+            DispatchKind::Binary => {
+                let mut sorted = targets.clone();
+                sorted.sort();
+                search(&sorted, &mut dests, &mut steps);
+            }
+            DispatchKind::PackedJumpTable if !targets.is_empty() => {
+                let slots = (targets.len()..=MAX_DISPATCH_SLOTS)
+                    .find(|n| {
+                        let mut indices: Vec<_> = targets.iter().map(|(s, _)| slot(s, *n)).collect();
+                        indices.sort();
+                        indices.windows(2).all(|w| w[0] != w[1])
+                    })
+                    .ok_or_else(|| {
+                        error(format!(
+                            "The {} selectors can't be told apart in a packed jump table of at most {MAX_DISPATCH_SLOTS} slots, use a binary dispatcher instead",
+                            targets.len()
+                        ))
+                    })?;
+
+                // Each function's slot jumps to a comparison with its selector, so that an
+                // unknown selector sharing the slot falls through
+                let mut lookup = vec![0; slots];
+                let mut comparisons = vec![];
+                for (s, l) in targets.iter() {
+                    dests += 1;
+                    lookup[slot(s, slots)] = dests;
+                    comparisons.extend([Step::Dest(dests), Step::Eq(*s, l.clone()), Step::Jump(0)]);
+                }
+                steps.push(Step::Lookup(lookup));
+                steps.extend(comparisons);
+            }
+            DispatchKind::PackedJumpTable => {}
+        }
+
+        // The last jump to the end falls through instead
+        if steps.last() == Some(&Step::Jump(0)) {
+            steps.pop();
+        }
+        if steps.iter().any(|s| matches!(s, Step::Jump(0) | Step::Lookup(_))) {
+            steps.push(Step::Dest(0));
+        }
+
+        Ok(Self { kind, targets, steps })
+    }
+
+    /// Returns the gas the dispatcher spends on a selector, up to and including the `jumpdest` of
+    /// its label, or `None` if the selector isn't dispatched.
+    ///
+    /// A packed jump table dispatcher copies its table entry to memory, which is assumed not to
+    /// have been expanded yet.
+    pub fn gas(&self, evm_version: &EVMVersion, selector: &[u8; 4]) -> Option<u64> {
+        let zero = if evm_version.has_push0() { 2 } else { 3 };
+        let dest = |id: &usize| self.steps.iter().position(|s| s == &Step::Dest(*id));
+        let mut gas = 0;
+        let mut i = 0;
+        while let Some(step) = self.steps.get(i) {
+            i += 1;
+            match step {
+                // PUSH0/PUSH1 CALLDATALOAD PUSH1 SHR
+                Step::Load => gas += zero + 9,
+                // DUP1 PUSH4 EQ PUSH2 JUMPI, and the label's JUMPDEST
+                Step::Eq(s, _) => {
+                    gas += 22;
+                    if s == selector {
+                        return Some(gas + 1)
+                    }
+                }
+                // DUP1 PUSH4 GT PUSH2 JUMPI
+                Step::Lt(pivot, id) => {
+                    gas += 22;
+                    if selector < pivot {
+                        i = dest(id)?;
+                    }
+                }
+                // PUSH2 JUMP
+                Step::Jump(id) => {
+                    gas += 11;
+                    i = dest(id)?;
+                }
+                // JUMPDEST
+                Step::Dest(_) => gas += 1,
+                // Indexing the slot and copying its entry take 41 gas, including a word of memory
+                // expansion, and loading, clearing and jumping to the entry 20 besides the zero
+                // pushes
+                Step::Lookup(lookup) => {
+                    gas += 3 * zero + 61;
+                    i = dest(&lookup[slot(selector, lookup.len())])?;
+                }
+            }
+        }
+        None
+    }
+
+    /// Generates the bytecode of the dispatcher at the given offset.
+    ///
+    /// Contract labels are pushed as placeholders in their own chunk, to be filled like any other
+    /// label call. A packed jump table is appended to the code as a utilized table, with its
+    /// destinations added to the label indices.
+    #[allow(clippy::too_many_arguments)]
+    pub fn gen(
+        &self,
+        evm_version: &EVMVersion,
+        offset: &mut usize,
+        jump_table: &mut JumpTable,
+        label_indices: &mut LabelIndices,
+        table_instances: &mut Jumps,
+        utilized_tables: &mut Vec<TableDefinition>,
+        span: &AstSpan,
+    ) -> Vec<(usize, Bytes)> {
+        let zero = literal_gen(evm_version, &[0u8; 32]);
+        let pieces = self
+            .steps
+            .iter()
+            .flat_map(|step| match step {
+                Step::Load => vec![Piece::Code(format!(
+                    "{zero}{}{}e0{}",
+                    Opcode::Calldataload,
+                    Opcode::Push1,
+                    Opcode::Shr
+                ))],
+                Step::Eq(s, l) => vec![
+                    Piece::Code(format!(
+                        "{}{}{}{}",
+                        Opcode::Dup1,
+                        Opcode::Push4,
+                        hex::encode(s),
+                        Opcode::Eq
+                    )),
+                    Piece::Label(l.clone()),
+                    Piece::Code(Opcode::Jumpi.to_string()),
+                ],
+                Step::Lt(pivot, id) => vec![
+                    Piece::Code(format!(
+                        "{}{}{}{}",
+                        Opcode::Dup1,
+                        Opcode::Push4,
+                        hex::encode(pivot),
+                        Opcode::Gt
+                    )),
+                    Piece::Push(*id),
+                    Piece::Code(Opcode::Jumpi.to_string()),
+                ],
+                Step::Jump(id) => vec![Piece::Push(*id), Piece::Code(Opcode::Jump.to_string())],
+                Step::Dest(id) => vec![Piece::Dest(*id)],
+                // slots dup2 mod 0x01 shl <table> add 0x02 swap1 0x1e codecopy 0x00 mload 0x00
+                // 0x00 mstore 0xffff and jump, clearing the scratch word the entry is copied to
+                Step::Lookup(lookup) => vec![
+                    Piece::Code(format!(
+                        "{}{}{}{}01{}",
+                        format_literal(format_even_bytes(format!("{:x}", lookup.len()))),
+                        Opcode::Dup2,
+                        Opcode::Mod,
+                        Opcode::Push1,
+                        Opcode::Shl
+                    )),
+                    Piece::Table,
+                    Piece::Code(format!(
+                        "{}{}02{}{}1e{}{zero}{}{zero}{zero}{}{}ffff{}{}",
+                        Opcode::Add,
+                        Opcode::Push1,
+                        Opcode::Swap1,
+                        Opcode::Push1,
+                        Opcode::Codecopy,
+                        Opcode::Mload,
+                        Opcode::Mstore,
+                        Opcode::Push2,
+                        Opcode::And,
+                        Opcode::Jump
+                    )),
+                ],
+            })
+            .collect::<Vec<_>>();
+
+        // Locate the destinations before pushing them
+        let start = *offset;
+        let mut dests = vec![0; self.steps.len()];
+        pieces.iter().fold(start, |pc, piece| match piece {
+            Piece::Code(code) => pc + code.len() / 2,
+            Piece::Dest(id) => {
+                dests[*id] = pc;
+                pc + 1
+            }
+            _ => pc + 3,
+        });
+
+        let table = format!("__DISPATCH_{start}");
+        let mut bytes = vec![];
+        let mut chunk = (start, String::new());
+        for piece in pieces {
+            match piece {
+                Piece::Code(code) => chunk.1.push_str(&code),
+                Piece::Label(label) => {
+                    let at = chunk.0 + chunk.1.len() / 2;
+                    if !chunk.1.is_empty() {
+                        bytes.push((chunk.0, Bytes(std::mem::take(&mut chunk.1))));
+                    }
+                    jump_table
+                        .insert(at, vec![Jump { label, bytecode_index: 0, span: span.clone() }]);
+                    bytes.push((at, Bytes(format!("{}xxxx", Opcode::Push2))));
+                    chunk.0 = at + 3;
+                }
+                Piece::Push(id) => chunk.1.push_str(&format!("{}{:04x}", Opcode::Push2, dests[id])),
+                Piece::Dest(_) => chunk.1.push_str(&Opcode::Jumpdest.to_string()),
+                Piece::Table => {
+                    table_instances.push(Jump {
+                        label: table.clone(),
+                        bytecode_index: chunk.0 + chunk.1.len() / 2,
+                        span: span.clone(),
+                    });
+                    chunk.1.push_str(&format!("{}xxxx", Opcode::Push2));
+                }
+            }
+        }
+        *offset = chunk.0 + chunk.1.len() / 2;
+        if !chunk.1.is_empty() {
+            bytes.push((chunk.0, Bytes(chunk.1)));
+        }
+
+        // The table holds the destination of each slot, as labels of the dispatcher
+        if let Some(Step::Lookup(lookup)) = self.steps.iter().find(|s| matches!(s, Step::Lookup(_)))
+        {
+            let statements = lookup
+                .iter()
+                .map(|id| {
+                    let label = format!("{table}_{id}");
+                    label_indices.insert(label.clone(), dests[*id]);
+                    Statement { ty: StatementType::LabelCall(label), span: span.clone() }
+                })
+                .collect();
+            utilized_tables.push(TableDefinition::new(
+                table,
+                TableKind::JumpTablePacked,
+                statements,
+                str_to_bytes32(&format!("{:x}", lookup.len() * 2)),
+                span.clone(),
+            ));
+        }
+
+        bytes
+    }
+}
+
+/// Appends a binary search of the sorted selectors to the steps, jumping to the end of the
+/// dispatcher if none match
+fn search(sorted: &[([u8; 4], String)], dests: &mut usize, steps: &mut Vec<Step>) {
+    if sorted.len() <= LEAF_SELECTORS {
+        steps.extend(sorted.iter().map(|(s, l)| Step::Eq(*s, l.clone())));
+        steps.push(Step::Jump(0));
+        return
+    }
+
+    // The upper half follows the comparison, and the lower half is jumped to
+    let mid = sorted.len() / 2;
+    *dests += 1;
+    let lower = *dests;
+    steps.push(Step::Lt(sorted[mid].0, lower));
+    search(&sorted[mid..], dests, steps);
+    steps.push(Step::Dest(lower));
+    search(&sorted[..mid], dests, steps);
+}
+
+/// Returns the slot of a selector in a packed jump table of the given number of slots
+fn slot(selector: &[u8; 4], slots: usize) -> usize {
+    u32::from_be_bytes(*selector) as usize % slots
+}
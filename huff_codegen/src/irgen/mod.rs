@@ -7,6 +7,12 @@ pub mod statements;
 /// Argument Call Module
 pub mod arg_calls;
 
+/// Static Stack Depth Module
+pub mod stack;
+
+/// Selector Dispatcher Generation Module
+pub mod dispatch;
+
 /// Prelude wraps common utilities.
 pub mod prelude {
     pub use super::{arg_calls::*, constants::*, statements::*};
@@ -0,0 +1,91 @@
+use huff_utils::prelude::*;
+use std::str::FromStr;
+
+/// Computes the depth of the stack before a statement of a macro, relative to the items the macro
+/// takes.
+///
+/// The depth is known statically by following the macro's statements in order, as if every jump
+/// falls through, and trusting the `takes` and `returns` of invoked macros. An argument is
+/// assumed to push a single item, unless it is an opcode passed to the macro.
+pub fn stack_depth(
+    contract: &Contract,
+    macro_def: &MacroDefinition,
+    invocation: Option<&MacroInvocation>,
+    statement: &Statement,
+) -> Option<isize> {
+    let mut depth = macro_def.takes as isize;
+    walk_stack(contract, macro_def, invocation, &macro_def.statements, statement, &mut depth)
+        .then_some(depth)
+}
+
+/// Applies the stack effect of each statement up to the given statement, returning whether it was
+/// found
+fn walk_stack(
+    contract: &Contract,
+    macro_def: &MacroDefinition,
+    invocation: Option<&MacroInvocation>,
+    statements: &[Statement],
+    statement: &Statement,
+    depth: &mut isize,
+) -> bool {
+    let effect = |(takes, returns): (usize, usize)| returns as isize - takes as isize;
+    let mut iter = statements.iter();
+    while let Some(s) = iter.next() {
+        if s == statement {
+            return true
+        }
+        *depth += match &s.ty {
+            StatementType::Opcode(o) => {
+                // The literal of a push is part of the push
+                if o.is_value_push() {
+                    iter.next();
+                }
+                effect(o.stack_io())
+            }
+            StatementType::MacroInvocation(mi) => contract
+                .find_macro_by_name(&mi.macro_name)
+                .map(|m| effect((m.takes, m.returns)))
+                .unwrap_or_default(),
+            StatementType::ArgCall(name) => {
+                let arg = macro_def
+                    .parameters
+                    .iter()
+                    .position(|p| p.name.as_deref() == Some(name))
+                    .and_then(|i| invocation.and_then(|mi| mi.args.get(i)));
+                match arg {
+                    Some(MacroArg::Ident(ident)) => {
+                        Opcode::from_str(ident).map(|o| effect(o.stack_io())).unwrap_or(1)
+                    }
+                    _ => 1,
+                }
+            }
+            StatementType::Label(l) => {
+                if walk_stack(contract, macro_def, invocation, &l.inner, statement, depth) {
+                    return true
+                }
+                0
+            }
+            StatementType::BuiltinFunctionCall(bf) => match bf.kind {
+                BuiltinFunctionKind::Tablesize |
+                BuiltinFunctionKind::Codesize |
+                BuiltinFunctionKind::Tablestart |
+                BuiltinFunctionKind::FunctionSignature |
+                BuiltinFunctionKind::EventHash |
+                BuiltinFunctionKind::Error |
+                BuiltinFunctionKind::RightPad |
+                BuiltinFunctionKind::Bytes => 1,
+                // The dispatcher jumps away with the selector it loaded on the stack
+                BuiltinFunctionKind::Dispatch => 1,
+                BuiltinFunctionKind::DynConstructorArg |
+                BuiltinFunctionKind::Verbatim |
+                BuiltinFunctionKind::AssertPc |
+                BuiltinFunctionKind::AssertStack => 0,
+            },
+            StatementType::Code(_) => 0,
+            StatementType::Literal(_) |
+            StatementType::Constant(_) |
+            StatementType::LabelCall(_) => 1,
+        };
+    }
+    false
+}
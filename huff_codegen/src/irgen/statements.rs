@@ -1,6 +1,9 @@
 use huff_utils::prelude::*;
 
-use crate::Codegen;
+use crate::{
+    irgen::{dispatch::Dispatcher, stack::stack_depth},
+    Codegen,
+};
 
 /// Generates the respective Bytecode for a given Statement
 #[allow(clippy::too_many_arguments)]
@@ -16,7 +19,9 @@ pub fn statement_gen<'a>(
     label_indices: &mut LabelIndices,
     table_instances: &mut Jumps,
     utilized_tables: &mut Vec<TableDefinition>,
+    source_map: &mut SourceMap,
     circular_codesize_invocations: &mut CircularCodeSizeIndices,
+    failed_pc_assertions: &mut Vec<(usize, usize, AstSpan)>,
     starting_offset: usize,
 ) -> Result<Vec<(usize, Bytes)>, CodegenError> {
     let mut bytes = vec![];
@@ -134,6 +139,8 @@ pub fn statement_gen<'a>(
                 table_instances.extend(res.table_instances);
                 label_indices.extend(res.label_indices);
                 utilized_tables.extend(res.utilized_tables);
+                source_map.extend(res.source_map);
+                failed_pc_assertions.extend(res.failed_pc_assertions);
 
                 // Increase offset by byte length of recursed macro
                 *offset += res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
@@ -227,6 +234,8 @@ pub fn statement_gen<'a>(
                             }
                         };
 
+                        // The macro is only sized here, at an offset other than its own, so
+                        // its PC assertions are checked where it is placed instead
                         let size = format_even_bytes(format!(
                             "{:02x}",
                             (res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2)
@@ -334,26 +343,14 @@ pub fn statement_gen<'a>(
                             format!("{}{}", Opcode::Push4, hex::encode(error.selector));
                         *offset += push_bytes.len() / 2;
                         bytes.push((starting_offset, Bytes(push_bytes)));
-                    } else if let Some(s) = &bf.args[0].name {
+                    } else {
+                        let s = raw_signature(bf)?;
                         let mut signature = [0u8; 4]; // Only keep first 4 bytes
-                        hash_bytes(&mut signature, s);
+                        hash_bytes(&mut signature, &s);
 
                         let push_bytes = format!("{}{}", Opcode::Push4, hex::encode(signature));
                         *offset += push_bytes.len() / 2;
                         bytes.push((starting_offset, Bytes(push_bytes)));
-                    } else {
-                        tracing::error!(
-                            target: "codegen",
-                            "MISSING FUNCTION INTERFACE PASSED TO __SIG: \"{}\"",
-                            bf.args[0].name.as_ref().unwrap()
-                        );
-                        return Err(CodegenError {
-                            kind: CodegenErrorKind::MissingFunctionInterface(
-                                bf.args[0].name.as_ref().unwrap().to_string(),
-                            ),
-                            span: bf.span.clone(),
-                            token: None,
-                        });
                     }
                 }
                 BuiltinFunctionKind::EventHash => {
@@ -384,26 +381,14 @@ pub fn statement_gen<'a>(
                         let push_bytes = format!("{}{hash}", Opcode::Push32);
                         *offset += push_bytes.len() / 2;
                         bytes.push((starting_offset, Bytes(push_bytes)));
-                    } else if let Some(s) = &bf.args[0].name {
+                    } else {
+                        let s = raw_signature(bf)?;
                         let mut hash = [0u8; 32];
-                        hash_bytes(&mut hash, s);
+                        hash_bytes(&mut hash, &s);
 
                         let push_bytes = format!("{}{}", Opcode::Push32, hex::encode(hash));
                         *offset += push_bytes.len() / 2;
                         bytes.push((starting_offset, Bytes(push_bytes)));
-                    } else {
-                        tracing::error!(
-                            target: "codegen",
-                            "MISSING EVENT INTERFACE PASSED TO __EVENT_HASH: \"{}\"",
-                            bf.args[0].name.as_ref().unwrap()
-                        );
-                        return Err(CodegenError {
-                            kind: CodegenErrorKind::MissingEventInterface(
-                                bf.args[0].name.as_ref().unwrap().to_string(),
-                            ),
-                            span: bf.span.clone(),
-                            token: None,
-                        });
                     }
                 }
                 BuiltinFunctionKind::Error => {
@@ -434,32 +419,20 @@ pub fn statement_gen<'a>(
                         let push_bytes = format!("{}{selector}", Opcode::Push32);
                         *offset += push_bytes.len() / 2;
                         bytes.push((starting_offset, Bytes(push_bytes)));
-                    } else if let Some(s) = &bf.args[0].name {
+                    } else {
+                        let s = raw_signature(bf)?;
                         let mut signature = [0u8; 4]; // Only keep first 4 bytes
-                        hash_bytes(&mut signature, s);
+                        hash_bytes(&mut signature, &s);
 
                         let push_bytes = format!("{}{}", Opcode::Push4, hex::encode(signature));
                         *offset += push_bytes.len() / 2;
                         bytes.push((starting_offset, Bytes(push_bytes)));
-                    } else {
-                        tracing::error!(
-                            target: "codegen",
-                            "MISSING ERROR DEFINITION PASSED TO __ERROR: \"{}\"",
-                            bf.args[0].name.as_ref().unwrap()
-                        );
-                        return Err(CodegenError {
-                            kind: CodegenErrorKind::MissingErrorDefinition(
-                                bf.args[0].name.as_ref().unwrap().to_string(),
-                            ),
-                            span: bf.span.clone(),
-                            token: None,
-                        });
                     }
                 }
                 BuiltinFunctionKind::RightPad => {
                     if bf.args.len() != 1 {
                         tracing::error!(
-                            target = "codegen",
+                            target: "codegen",
                             "Incorrect number of arguments passed to __RIGHTPAD, should be 1: {}",
                             bf.args.len()
                         );
@@ -482,7 +455,7 @@ pub fn statement_gen<'a>(
                 BuiltinFunctionKind::DynConstructorArg => {
                     if bf.args.len() != 2 {
                         tracing::error!(
-                            target = "codegen",
+                            target: "codegen",
                             "Incorrect number of arguments passed to __CODECOPY_DYN_ARG, should be 2: {}",
                             bf.args.len()
                         );
@@ -503,7 +476,7 @@ pub fn statement_gen<'a>(
                     // 2 bytes.
                     if arg_index.len() != 2 || dest_offset.len() > 4 {
                         tracing::error!(
-                            target = "codegen",
+                            target: "codegen",
                             "Incorrect number of bytes in argument passed to __CODECOPY_DYN_ARG. Should be (1 byte, <= 2 bytes)"
                         );
                         return Err(CodegenError {
@@ -534,7 +507,7 @@ pub fn statement_gen<'a>(
                 BuiltinFunctionKind::Verbatim => {
                     if bf.args.len() != 1 {
                         tracing::error!(
-                            target = "codegen",
+                            target: "codegen",
                             "Incorrect number of arguments passed to __INJECT, should be 1: {}",
                             bf.args.len()
                         );
@@ -577,6 +550,109 @@ pub fn statement_gen<'a>(
 
                     bytes.push((starting_offset, Bytes(push_bytes)));
                 }
+                BuiltinFunctionKind::Bytes => {
+                    // Only strings are accepted, rather than literals or identifiers
+                    let is_string =
+                        bf.args.first().is_some_and(|a| a.arg_type.as_deref() == Some("string"));
+                    let left = match bf.args.get(1).and_then(|a| a.name.as_deref()) {
+                        Some("left") if is_string && bf.args.len() == 2 => true,
+                        Some("right") if is_string && bf.args.len() == 2 => false,
+                        None if is_string && bf.args.len() == 1 => false,
+                        _ => {
+                            tracing::error!(
+                                target: "codegen",
+                                "Invalid arguments passed to __BYTES, should be a string and an optional alignment"
+                            );
+                            return Err(CodegenError {
+                                kind: CodegenErrorKind::InvalidArguments(String::from(
+                                    "Invalid arguments passed to __BYTES, should be a string and an optional \"left\" or \"right\" alignment"
+                                )),
+                                span: bf.span.clone(),
+                                token: None,
+                            });
+                        }
+                    };
+
+                    let str = bf.args[0].name.as_ref().unwrap();
+                    if str.len() > 32 {
+                        tracing::error!(
+                            target: "codegen",
+                            "STRING LITERAL LONGER THAN 32 BYTES: \"{}\"",
+                            str
+                        );
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::BytesOverflow(str.to_string()),
+                            span: bf.span.clone(),
+                            token: None,
+                        });
+                    }
+
+                    // Right aligned bytes are pushed with the smallest push, and left aligned
+                    // bytes are padded to a full word
+                    let hex = hex::encode(str.as_bytes());
+                    let push_bytes = match (left, hex.is_empty()) {
+                        (true, _) => {
+                            format!("{}{hex}{}", Opcode::Push32, "0".repeat(64 - hex.len()))
+                        }
+                        (false, true) => literal_gen(evm_version, &[0u8; 32]),
+                        (false, false) => format_literal(hex),
+                    };
+                    *offset += push_bytes.len() / 2;
+                    bytes.push((starting_offset, Bytes(push_bytes)));
+                }
+                BuiltinFunctionKind::AssertPc | BuiltinFunctionKind::AssertStack => {
+                    let name = if bf.kind == BuiltinFunctionKind::AssertPc {
+                        "__ASSERT_PC"
+                    } else {
+                        "__ASSERT_STACK"
+                    };
+                    let expected = match bf.args.as_slice() {
+                        [arg] => arg.name.as_deref().and_then(|n| hex_to_usize(n).ok()),
+                        _ => None,
+                    };
+                    let Some(expected) = expected else {
+                        tracing::error!(
+                            target: "codegen",
+                            "Invalid arguments passed to {}, should be a single literal",
+                            name
+                        );
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::InvalidArguments(format!(
+                                "Invalid arguments passed to {name}, should be a single literal"
+                            )),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    };
+
+                    // Assertions are checked at compile time, and emit no bytes. A PC
+                    // assertion is only reported once the macro is at its final placement.
+                    if bf.kind == BuiltinFunctionKind::AssertPc {
+                        if *offset != expected {
+                            failed_pc_assertions.push((expected, *offset, bf.span.clone()));
+                        }
+                    } else if let Some(depth) =
+                        stack_depth(contract, macro_def, mis.last().map(|(_, mi)| mi), s)
+                            .filter(|depth| *depth != expected as isize)
+                    {
+                        let kind = CodegenErrorKind::FailedStackAssertion(expected, depth);
+                        tracing::error!(target: "codegen", "FAILED ASSERTION IN MACRO \"{}\": {:?}", macro_def.name, kind);
+                        return Err(CodegenError { kind, span: bf.span.clone(), token: None })
+                    }
+                }
+                BuiltinFunctionKind::Dispatch => {
+                    let dispatcher = Dispatcher::new(contract, bf)?;
+                    tracing::info!(target: "codegen", "GENERATING {:?} DISPATCHER FOR {} FUNCTIONS", dispatcher.kind, dispatcher.targets.len());
+                    bytes = dispatcher.gen(
+                        evm_version,
+                        offset,
+                        jump_table,
+                        label_indices,
+                        table_instances,
+                        utilized_tables,
+                        &bf.span,
+                    );
+                }
             }
         }
         sty => {
@@ -592,3 +668,20 @@ pub fn statement_gen<'a>(
 
     Ok(bytes)
 }
+
+/// Canonicalizes the raw signature passed to a signature builtin. An argument without parameters
+/// is the name of a missing definition, which is hashed as is.
+fn raw_signature(bf: &BuiltinFunctionCall) -> Result<String, CodegenError> {
+    let raw = bf.args[0].name.clone().unwrap_or_default();
+    if !raw.contains('(') {
+        return Ok(raw)
+    }
+    canonicalize_signature(&raw).ok_or_else(|| {
+        tracing::error!(target: "codegen", "INVALID SIGNATURE PASSED TO BUILTIN: \"{}\"", raw);
+        CodegenError {
+            kind: CodegenErrorKind::InvalidSignature(raw),
+            span: bf.span.clone(),
+            token: None,
+        }
+    })
+}
@@ -16,10 +16,16 @@ use huff_utils::{
     types::EToken,
 };
 use regex::Regex;
-use std::{cmp::Ordering, collections::HashMap, fs, path::Path, sync::Arc};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+    sync::Arc,
+};
 
 mod irgen;
-use crate::irgen::prelude::*;
+use crate::irgen::{dispatch::Dispatcher, prelude::*};
 
 /// ### Codegen
 ///
@@ -296,12 +302,29 @@ impl Codegen {
         let mut label_indices = LabelIndices::new();
         let mut table_instances = Jumps::new();
         let mut utilized_tables: Vec<TableDefinition> = Vec::new();
+        let mut source_map = SourceMap::new();
+        let mut current_label: Option<String> = None;
+        let mut failed_pc_assertions = Vec::new();
+        // Only a root compilation is at its final placement, nested ones report to their parent
+        let placed = circular_codesize_invocations.is_none();
         let mut ccsi = CircularCodeSizeIndices::new();
         let circular_codesize_invocations = circular_codesize_invocations.unwrap_or(&mut ccsi);
 
         // Loop through all intermediate bytecode representations generated from the AST
         for ir_byte in ir_bytes.iter() {
             let starting_offset = offset;
+            if let IRByteType::Statement(Statement { ty: StatementType::Label(l), .. }) =
+                &ir_byte.ty
+            {
+                current_label = Some(l.name.clone());
+            }
+            source_map.push(SourceMapEntry {
+                pc: starting_offset,
+                macro_name: macro_def.name.clone(),
+                label: current_label.clone(),
+                span: ir_byte.span.clone(),
+                test: macro_def.test,
+            });
             match &ir_byte.ty {
                 IRByteType::Bytes(b) => {
                     offset += b.0.len() / 2;
@@ -331,7 +354,9 @@ impl Codegen {
                         &mut label_indices,
                         &mut table_instances,
                         &mut utilized_tables,
+                        &mut source_map,
                         circular_codesize_invocations,
+                        &mut failed_pc_assertions,
                         starting_offset,
                     )?;
                     bytes.append(&mut push_bytes);
@@ -370,6 +395,8 @@ impl Codegen {
                 &mut jump_table,
                 &mut label_indices,
                 &mut table_instances,
+                &mut source_map,
+                &mut failed_pc_assertions,
                 bytes,
             )?;
         } else {
@@ -390,7 +417,21 @@ impl Codegen {
             &macro_def.name,
         )?;
 
-        Ok(BytecodeRes { bytes, label_indices, unmatched_jumps, table_instances, utilized_tables })
+        if let Some((expected, found, span)) = failed_pc_assertions.first().filter(|_| placed) {
+            let kind = CodegenErrorKind::FailedPcAssertion(*expected, *found);
+            tracing::error!(target: "codegen", "FAILED ASSERTION IN MACRO \"{}\": {:?}", macro_def.name, kind);
+            return Err(CodegenError { kind, span: span.clone(), token: None })
+        }
+
+        Ok(BytecodeRes {
+            bytes,
+            label_indices,
+            unmatched_jumps,
+            table_instances,
+            utilized_tables,
+            source_map,
+            failed_pc_assertions,
+        })
     }
 
     /// Helper associated function to fill unmatched jump dests.
@@ -551,6 +592,8 @@ impl Codegen {
         jump_table: &mut JumpTable,
         label_indices: &mut LabelIndices,
         table_instances: &mut Jumps,
+        source_map: &mut SourceMap,
+        failed_pc_assertions: &mut Vec<(usize, usize, AstSpan)>,
         mut bytes: Vec<(usize, Bytes)>,
     ) -> Result<Vec<(usize, Bytes)>, CodegenError> {
         for macro_def in contract.macros.iter().filter(|m| m.outlined) {
@@ -566,7 +609,8 @@ impl Codegen {
                 *offset + 1,
                 mis,
                 false,
-                None,
+                // Functions are placed with the macro they are appended to
+                Some(&mut CircularCodeSizeIndices::new()),
             )?;
 
             for j in res.unmatched_jumps.iter_mut() {
@@ -582,6 +626,15 @@ impl Codegen {
             }
             table_instances.extend(res.table_instances);
             label_indices.extend(res.label_indices);
+            source_map.push(SourceMapEntry {
+                pc: *offset,
+                macro_name: macro_def.name.clone(),
+                label: None,
+                span: macro_def.span.clone(),
+                test: macro_def.test,
+            });
+            source_map.extend(res.source_map);
+            failed_pc_assertions.extend(res.failed_pc_assertions);
 
             let macro_code_len = res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
 
@@ -837,4 +890,98 @@ impl Codegen {
         // Return the abi
         Ok(abi)
     }
+
+    /// Checks the raw signatures passed to the `__FUNC_SIG`, `__EVENT_HASH` and `__ERROR`
+    /// builtins against the contract's definitions, returning a warning for each signature, or bare
+    /// name, that matches none of them. A typo in a raw signature compiles to the wrong selector.
+    ///
+    /// Malformed signatures are left for code generation to error on.
+    pub fn check_signatures(contract: &Contract) -> Vec<CodegenError> {
+        // Statements are popped in source order, with a label's statements following it
+        let mut statements =
+            contract.macros.iter().flat_map(|m| m.statements.iter()).rev().collect::<Vec<_>>();
+        let mut warnings = vec![];
+        while let Some(s) = statements.pop() {
+            let bf = match &s.ty {
+                StatementType::Label(l) => {
+                    statements.extend(l.inner.iter().rev());
+                    continue
+                }
+                StatementType::BuiltinFunctionCall(bf) => bf,
+                _ => continue,
+            };
+            let Some(raw) = bf.args.first().and_then(|a| a.name.as_ref()) else { continue };
+            // A bare name is matched against the names of definitions
+            let bare = !raw.contains('(');
+            let signature = if bare {
+                raw.clone()
+            } else if let Some(signature) = canonicalize_signature(raw) {
+                signature
+            } else {
+                continue
+            };
+            let matches = |name: &str, canonical: String| {
+                if bare {
+                    name == signature
+                } else {
+                    canonical == signature
+                }
+            };
+            let (builtin, defined) = match bf.kind {
+                BuiltinFunctionKind::FunctionSignature => (
+                    "__FUNC_SIG",
+                    contract.functions.iter().any(|f| matches(&f.name, f.canonical_signature())) ||
+                        contract
+                            .errors
+                            .iter()
+                            .any(|e| matches(&e.name, e.canonical_signature())),
+                ),
+                BuiltinFunctionKind::EventHash => (
+                    "__EVENT_HASH",
+                    contract.events.iter().any(|e| matches(&e.name, e.canonical_signature())),
+                ),
+                BuiltinFunctionKind::Error => (
+                    "__ERROR",
+                    contract.errors.iter().any(|e| matches(&e.name, e.canonical_signature())),
+                ),
+                _ => continue,
+            };
+            if !defined {
+                tracing::warn!(target: "codegen", "UNDEFINED SIGNATURE PASSED TO {}: \"{}\"", builtin, signature);
+                warnings.push(CodegenError {
+                    kind: CodegenErrorKind::UndefinedSignature(builtin.to_string(), signature),
+                    span: bf.span.clone(),
+                    token: None,
+                });
+            }
+        }
+        warnings
+    }
+
+    /// Returns the gas the first `__DISPATCH` dispatcher of the contract spends on each function
+    /// selector it dispatches, up to and including the `jumpdest` of the function's label.
+    ///
+    /// Returns an empty map if the contract has no valid `__DISPATCH` invocation.
+    pub fn dispatch_gas(evm_version: &EVMVersion, contract: &Contract) -> BTreeMap<[u8; 4], u64> {
+        // Statements are popped in source order, with a label's statements following it
+        let mut statements =
+            contract.macros.iter().flat_map(|m| m.statements.iter()).rev().collect::<Vec<_>>();
+        while let Some(s) = statements.pop() {
+            match &s.ty {
+                StatementType::Label(l) => statements.extend(l.inner.iter().rev()),
+                StatementType::BuiltinFunctionCall(bf)
+                    if bf.kind == BuiltinFunctionKind::Dispatch =>
+                {
+                    let Ok(dispatcher) = Dispatcher::new(contract, bf) else { continue };
+                    return dispatcher
+                        .targets
+                        .iter()
+                        .filter_map(|(s, _)| Some((*s, dispatcher.gas(evm_version, s)?)))
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        BTreeMap::new()
+    }
 }
@@ -18,12 +18,12 @@ use huff_utils::{
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 use rayon::prelude::*;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     ffi::OsString,
     fs,
     iter::Iterator,
     path::PathBuf,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 use tracing_subscriber::{filter::Directive, EnvFilter};
 
@@ -82,6 +82,8 @@ pub struct Compiler<'a, 'l> {
     pub cached: bool,
     /// The implementation of a FileReader
     pub file_provider: Arc<dyn FileProvider<'a>>,
+    /// Warnings raised while compiling, which don't fail the compilation
+    pub warnings: Arc<Mutex<Vec<CompilerError>>>,
 }
 
 impl<'a, 'l> Compiler<'a, 'l> {
@@ -113,6 +115,7 @@ impl<'a, 'l> Compiler<'a, 'l> {
             bytecode: false,
             cached,
             file_provider: Arc::new(FileSystemFileProvider {}),
+            warnings: Arc::new(Mutex::new(vec![])),
         }
     }
 
@@ -144,6 +147,7 @@ impl<'a, 'l> Compiler<'a, 'l> {
             bytecode: false,
             cached: false,
             file_provider: Arc::new(InMemoryFileProvider::new(file_sources)),
+            warnings: Arc::new(Mutex::new(vec![])),
         }
     }
 
@@ -343,16 +347,13 @@ impl<'a, 'l> Compiler<'a, 'l> {
                 tracing::debug!(target: "core", "GOT FULL SOURCE FOR PATH: {:?}", file.path);
 
                 // Perform Lexical Analysis
-                // Create a new lexer from the FileSource, flattening dependencies
-                let lexer = Lexer::new(full_source.source);
-
-                // Grab the tokens from the lexer
-                let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+                let tokens = Compiler::lex(&full_source)?;
                 tracing::info!(target: "core", "LEXICAL ANALYSIS COMPLETE FOR \"{}\"", file.path);
                 tracing::info!(target: "core", "└─ TOKEN COUNT: {}", tokens.len());
 
                 // Parser incantation
                 let mut parser = Parser::new(tokens, Some(file.path.clone()));
+                parser.defined_constants = self.overridden_constants();
 
                 // Parse into an AST
                 let parse_res = parser.parse().map_err(CompilerError::ParserError);
@@ -365,6 +366,29 @@ impl<'a, 'l> Compiler<'a, 'l> {
             .collect::<Result<Vec<Contract>, Arc<CompilerError>>>()
     }
 
+    /// Lexical Analysis
+    ///
+    /// Lexes a flattened source, attributing the span of each token and lexical error to the
+    /// file it originated from, so that contracts and errors can be mapped back to their sources.
+    pub fn lex(full_source: &FullFileSource) -> Result<Vec<Token>, CompilerError> {
+        Lexer::new(full_source.source)
+            .map(|x| match x {
+                Ok(mut t) => {
+                    if let Some(span) = full_source.relative_span(&t.span) {
+                        t.span = span;
+                    }
+                    Ok(t)
+                }
+                Err(mut e) => {
+                    if let Some(span) = full_source.relative_span(&e.span) {
+                        e.span = span;
+                    }
+                    Err(CompilerError::LexicalError(e))
+                }
+            })
+            .collect()
+    }
+
     /// Artifact Generation
     ///
     /// Compiles a FileSource into an Artifact.
@@ -381,16 +405,13 @@ impl<'a, 'l> Compiler<'a, 'l> {
         tracing::debug!(target: "core", "GOT FULL SOURCE FOR PATH: {:?}", file.path);
 
         // Perform Lexical Analysis
-        // Create a new lexer from the FileSource, flattening dependencies
-        let lexer = Lexer::new(full_source.source);
-
-        // Grab the tokens from the lexer
-        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let tokens = Compiler::lex(&full_source)?;
         tracing::info!(target: "core", "LEXICAL ANALYSIS COMPLETE FOR \"{}\"", file.path);
         tracing::info!(target: "core", "└─ TOKEN COUNT: {}", tokens.len());
 
         // Parser incantation
         let mut parser = Parser::new(tokens, Some(file.path.clone()));
+        parser.defined_constants = self.overridden_constants();
 
         // Parse into an AST
         let parse_res = parser.parse().map_err(CompilerError::ParserError);
@@ -399,6 +420,16 @@ impl<'a, 'l> Compiler<'a, 'l> {
         contract.add_override_constants(&self.constant_overrides);
         tracing::info!(target: "core", "PARSED CONTRACT [{}]", file.path);
 
+        // Warn about raw signatures passed to builtins that match no definition
+        let warnings = Codegen::check_signatures(&contract).into_iter().map(|mut w| {
+            w.span
+                .0
+                .iter_mut()
+                .for_each(|s| s.file = s.file.take().or_else(|| Some(Arc::clone(&file))));
+            CompilerError::CodegenError(w)
+        });
+        self.warnings.lock().unwrap().extend(warnings);
+
         // Primary Bytecode Generation
         let mut cg = Codegen::new();
         let main_bytecode = match Codegen::generate_main_bytecode(
@@ -415,7 +446,7 @@ impl<'a, 'l> Compiler<'a, 'l> {
                         .0
                         .into_iter()
                         .map(|mut s| {
-                            s.file = Some(Arc::clone(&file));
+                            s.file = s.file.or_else(|| Some(Arc::clone(&file)));
                             s
                         })
                         .collect::<Vec<Span>>(),
@@ -447,7 +478,7 @@ impl<'a, 'l> Compiler<'a, 'l> {
                             .0
                             .into_iter()
                             .map(|mut s| {
-                                s.file = Some(Arc::clone(&file));
+                                s.file = s.file.or_else(|| Some(Arc::clone(&file)));
                                 s
                             })
                             .collect::<Vec<Span>>();
@@ -511,8 +542,25 @@ impl<'a, 'l> Compiler<'a, 'l> {
         fs: Arc<FileSource>,
         remapper: &Remapper,
         reader: Arc<dyn FileProvider<'a>>,
+    ) -> Result<Arc<FileSource>, Arc<CompilerError>> {
+        Self::recurse_included_deps(fs, remapper, reader, &[], &mut BTreeMap::new())
+    }
+
+    /// Recurses the dependencies of a file included through a chain of files, erroring if the
+    /// file includes a file in the chain.
+    ///
+    /// Files are only read and resolved once, keyed by their canonical path, however many files
+    /// include them. Includes are resolved one after the other for this reason.
+    fn recurse_included_deps(
+        fs: Arc<FileSource>,
+        remapper: &Remapper,
+        reader: Arc<dyn FileProvider<'a>>,
+        chain: &[String],
+        resolved: &mut BTreeMap<String, Arc<FileSource>>,
     ) -> Result<Arc<FileSource>, Arc<CompilerError>> {
         tracing::debug!(target: "core", "RECURSING DEPENDENCIES FOR {}", fs.path);
+        let mut chain = chain.to_vec();
+        chain.push(fs.path.clone());
         let mut new_fs = FileSource { path: fs.path.clone(), ..Default::default() };
         let file_source = if let Some(s) = &fs.source {
             s.clone()
@@ -555,31 +603,49 @@ impl<'a, 'l> Compiler<'a, 'l> {
         if !localized_imports.is_empty() {
             tracing::info!(target: "core", "LOCALIZED IMPORTS {:?}", localized_imports);
         }
-        let import_bufs: Vec<PathBuf> = reader.transform_paths(&localized_imports)?;
-        let potentials: Result<Vec<Arc<FileSource>>, CompilerError> =
-            Self::fetch_sources(import_bufs, reader.clone()).into_iter().collect();
-        let mut file_sources = match potentials {
-            Ok(p) => p,
-            Err(e) => return Err(Arc::new(e)),
-        };
-        if !file_sources.is_empty() {
-            tracing::info!(target: "core", "FETCHED {} FILE SOURCES", file_sources.len());
+
+        // Detect include cycles, which would otherwise recurse infinitely
+        for import in localized_imports.iter() {
+            let canonical = FileSource::canonical_path(import);
+            if let Some(start) =
+                chain.iter().position(|p| FileSource::canonical_path(p) == canonical)
+            {
+                tracing::error!(target: "core", "INCLUDE CYCLE DETECTED FOR \"{}\"", import);
+                let mut cycle = chain[start..].to_vec();
+                cycle.push(chain[start].clone());
+                return Err(Arc::new(CompilerError::IncludeCycle(cycle)))
+            }
         }
+        let import_bufs: Vec<PathBuf> = reader.transform_paths(&localized_imports)?;
 
-        // Now that we have all the file sources, we have to recurse and get their source
-        file_sources = file_sources
-            .into_par_iter()
-            .map(|inner_fs| match Self::recurse_deps(Arc::clone(&inner_fs), remapper, reader.clone()) {
+        // Read and resolve each file at its first include, in include order. Each further include
+        // of a file gets its own copy, since a file may be included into more than one namespace.
+        let mut dependencies = vec![];
+        for pb in import_bufs {
+            let path = FileSource::canonical_path(&pb.to_string_lossy());
+            if let Some(fs) = resolved.get(&path) {
+                dependencies.push(FileSource::deep_copy(fs));
+                continue
+            }
+            let inner_fs = reader.read_file(pb).map_err(Arc::new)?;
+            let new_fs = match Self::recurse_included_deps(
+                Arc::clone(&inner_fs),
+                remapper,
+                reader.clone(),
+                &chain,
+                resolved,
+            ) {
                 Ok(new_fs) => new_fs,
+                Err(e) if matches!(*e, CompilerError::IncludeCycle(_)) => return Err(e),
                 Err(e) => {
                     tracing::error!(target: "core", "NESTED DEPENDENCY RESOLUTION FAILED: \"{:?}\"", e);
-                    Arc::clone(&inner_fs)
+                    inner_fs
                 }
-            })
-            .collect();
-
-        // Finally set the parent deps
-        new_fs.dependencies = Some(file_sources);
+            };
+            resolved.insert(path, Arc::clone(&new_fs));
+            dependencies.push(new_fs);
+        }
+        new_fs.dependencies = Some(dependencies);
 
         Ok(Arc::new(new_fs))
     }
@@ -623,6 +689,11 @@ impl<'a, 'l> Compiler<'a, 'l> {
         });
     }
 
+    /// The names of the overridden constants, which conditional directives treat as defined
+    pub fn overridden_constants(&self) -> BTreeSet<String> {
+        self.constant_overrides.iter().flat_map(|o| o.keys().map(|k| k.to_string())).collect()
+    }
+
     /// Derives Constructor Input Arguments
     pub fn get_constructor_args(&self) -> Vec<String> {
         match &self.construct_args {
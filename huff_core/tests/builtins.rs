@@ -648,3 +648,280 @@ fn test_rightpad_builtin() {
         )
     );
 }
+
+#[test]
+fn test_bytes_builtin() {
+    let source: &str = r#"
+        #define macro MAIN() = takes (0) returns (0) {
+            "Unauthorized"
+            __BYTES("moose")
+            __BYTES("moose", right)
+            __BYTES("Hello, World!", left)
+            ""
+            label:
+                "ok"
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+
+    // Derive storage pointers
+    contract.derive_storage_pointers();
+
+    // Strings are pushed with the smallest push of their bytes, unless left aligned
+    let r_bytes = Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap();
+    assert_eq!(
+        r_bytes,
+        String::from(
+            "6b556e617574686f72697a6564646d6f6f7365646d6f6f73657f48656c6c6f2c20576f726c6421000000000000000000000000000000000000005f5b616f6b"
+        )
+    );
+}
+
+#[test]
+fn test_bytes_builtin_overflow() {
+    let source: &str = r#"
+        #define macro MAIN() = takes (0) returns (0) {
+            "This string is much longer than thirty two bytes"
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+
+    // Derive storage pointers
+    contract.derive_storage_pointers();
+
+    let e = Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap_err();
+    assert_eq!(
+        e.kind,
+        CodegenErrorKind::BytesOverflow(String::from(
+            "This string is much longer than thirty two bytes"
+        ))
+    );
+    let span = &e.span.0[0];
+    assert_eq!(
+        &source[span.start..=span.end],
+        "\"This string is much longer than thirty two bytes\""
+    );
+
+    // The alignment must be left or right
+    let source: &str = r#"
+        #define macro MAIN() = takes (0) returns (0) {
+            __BYTES("moose", center)
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    let e = Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap_err();
+    assert!(matches!(e.kind, CodegenErrorKind::InvalidArguments(_)));
+
+    // Only strings are accepted, not literals or identifiers
+    for arg in ["0x1234", "moose", "0x1234, left"] {
+        let source =
+            format!("#define macro MAIN() = takes (0) returns (0) {{\n    __BYTES({arg})\n}}");
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source.source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let contract = parser.parse().unwrap();
+        let e =
+            Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap_err();
+        assert!(matches!(e.kind, CodegenErrorKind::InvalidArguments(_)), "{arg}");
+    }
+}
+
+#[test]
+fn test_assert_pc_builtin() {
+    let source = |pc: &str| {
+        format!(
+            r#"
+            #define macro MAIN() = takes (0) returns (0) {{
+                0x00 calldataload 0xe0 shr
+                dup1 0x01 eq target jumpi
+                0x00 dup1 revert
+                target:
+                    __ASSERT_PC({pc})
+                    stop
+            }}
+            "#
+        )
+    };
+
+    // Parse tokens
+    let source_ok = source("0x11");
+    let flattened_source = FullFileSource { source: &source_ok, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // The assertion emits no bytes
+    let r_bytes = Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap();
+    assert_eq!(r_bytes, "5f3560e01c80600114610010575f80fd5b00");
+
+    let source_err = source("0x10");
+    let flattened_source = FullFileSource { source: &source_err, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    let e = Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::FailedPcAssertion(0x10, 0x11));
+}
+
+#[test]
+fn test_assert_pc_in_sized_macro() {
+    let source: &str = r#"
+        #define macro CONSTRUCTOR() = takes (0) returns (0) {
+            0x01 pop __codesize(MAIN)
+        }
+
+        #define macro MAIN() = takes (0) returns (0) {
+            0x00 calldataload 0xe0 shr
+            dup1 0x01 eq target jumpi
+            0x00 dup1 revert
+            target:
+                __ASSERT_PC(0x11)
+                stop
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // Sizing MAIN at the constructor's offset doesn't check its assertions
+    let (constructor_bytecode, _) =
+        Codegen::generate_constructor_bytecode(&EVMVersion::default(), &contract, None).unwrap();
+    assert_eq!(constructor_bytecode, "6001506012");
+    let main_bytecode =
+        Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap();
+    assert_eq!(main_bytecode, "5f3560e01c80600114610010575f80fd5b00");
+}
+
+#[test]
+fn test_assert_stack_builtin() {
+    let source: &str = r#"
+        #define macro ADD(op) = takes (2) returns (1) {
+            __ASSERT_STACK(2)
+            <op>
+            __ASSERT_STACK(1)
+        }
+
+        #define macro MAIN() = takes (0) returns (0) {
+            0x00 calldataload 0xe0 shr      // [sig]
+            dup1 0x01 eq target jumpi       // [sig]
+            __ASSERT_STACK(1)
+            0x00 dup1 revert
+            target:
+                0x01 ADD(add)               // [sig + 1]
+                push2 0x0001 pop            // [sig + 1]
+                __ASSERT_STACK(1)
+                __ASSERT_STACK(2)
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // The depth follows the macro's statements, and the takes and returns of invoked macros
+    let e = Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None).unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::FailedStackAssertion(2, 1));
+    assert_eq!(e.span.0[0].start, source.rfind("__ASSERT_STACK").unwrap());
+}
+
+#[test]
+fn test_dispatch_builtin() {
+    let source = |dispatch: &str| {
+        format!(
+            r#"
+            #define function transfer(address,uint256) nonpayable returns (bool)
+            #define function balanceOf(address) view returns (uint256)
+            #define jumptable SELECTORS {{
+                give query
+            }}
+
+            #define macro DISPATCHER() = takes (0) returns (1) {{
+                {dispatch}
+            }}
+
+            #define macro MAIN() = takes (0) returns (0) {{
+                DISPATCHER()
+                0x00 dup1 revert
+                transfer:
+                give:
+                    stop
+                balanceOf:
+                query:
+                    stop
+            }}
+            "#
+        )
+    };
+    let bytecode = |dispatch: &str| {
+        let source = source(dispatch);
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source.source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let contract = parser.parse().unwrap();
+        Codegen::generate_main_bytecode(&EVMVersion::default(), &contract, None)
+    };
+
+    // Functions are dispatched to the labels named after them, or to those in the jump table
+    assert_eq!(
+        bytecode("__DISPATCH(linear)").unwrap(),
+        "5f3560e01c8063a9059cbb1461001e57806370a0823114610021575f80fd5b5b005b5b00"
+    );
+    assert_eq!(
+        bytecode("__DISPATCH(linear, SELECTORS)").unwrap(),
+        "5f3560e01c8063a9059cbb1461001f57806370a0823114610022575f80fd5b5b005b5b00"
+    );
+
+    // A binary search compares few enough selectors in sorted order
+    assert_eq!(
+        bytecode("__DISPATCH(binary)").unwrap(),
+        "5f3560e01c806370a0823114610021578063a9059cbb1461001e575f80fd5b5b005b5b00"
+    );
+
+    // The selectors take distinct slots of a four slot jump table, appended to the code
+    assert_eq!(
+        bytecode("__DISPATCH(packed_jumptable)").unwrap(),
+        "5f3560e01c6004810660011b61004601600290601e395f515f5f5261ffff16565b8063a9059cbb146100405761\
+         003c565b806370a0823114610043575b5f80fd5b5b005b5b00003c0030003c0020"
+    );
+
+    // The dispatcher leaves the selector on the stack
+    assert!(bytecode("__DISPATCH(linear) __ASSERT_STACK(1)").is_ok());
+    let e = bytecode("__DISPATCH(packed_jumptable) __ASSERT_STACK(0)").unwrap_err();
+    assert_eq!(e.kind, CodegenErrorKind::FailedStackAssertion(0, 1));
+
+    for dispatch in ["__DISPATCH()", "__DISPATCH(quadratic)", "__DISPATCH(linear, MISSING)"] {
+        let e = bytecode(dispatch).unwrap_err();
+        assert!(matches!(e.kind, CodegenErrorKind::InvalidArguments(_)), "{dispatch}");
+    }
+}
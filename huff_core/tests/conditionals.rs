@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+mod common;
+
+const MAIN: &str = r#"
+#ifdef L2
+    #include "./L2.huff"
+#else
+    #include "./Mainnet.huff"
+#endif
+
+#define macro MAIN() = takes (0) returns (0) {
+    [GAS_TOKEN]
+    #ifdef DEBUG
+        0xdd pop
+    #endif
+}
+"#;
+
+const MAINNET: &str = r#"
+#define constant GAS_TOKEN = 0x01
+"#;
+
+const L2: &str = r#"
+#include "./Bridge.huff"
+#define constant GAS_TOKEN = 0x02
+"#;
+
+const BRIDGE: &str = r#"
+#define constant BRIDGE = 0x03
+"#;
+
+fn new_compiler<'a>(evm_version: &'a EVMVersion, overrides: &[&'a str]) -> Compiler<'a, 'a> {
+    let constant_overrides = overrides
+        .iter()
+        .map(|name| (*name, str_to_bytes32("01")))
+        .collect::<BTreeMap<&str, Literal>>();
+    common::compiler(
+        evm_version,
+        &[("main.huff", MAIN), ("Mainnet.huff", MAINNET), ("L2.huff", L2), ("Bridge.huff", BRIDGE)],
+        Some(constant_overrides),
+    )
+}
+
+fn constants(compiler: &Compiler) -> Vec<String> {
+    let contract = &compiler.grab_contracts().unwrap()[0];
+    let constants = contract.constants.lock().unwrap();
+    constants.iter().map(|c| c.name.clone()).collect()
+}
+
+#[test]
+fn test_conditional_compilation_with_overrides() {
+    // Files included from inactive branches are excluded, along with the files they include
+    let evm_version = EVMVersion::default();
+    let compiler = new_compiler(&evm_version, &[]);
+    assert_eq!(constants(&compiler), vec!["GAS_TOKEN"]);
+    assert!(compiler.execute().unwrap()[0].bytecode.ends_with("6001"));
+
+    // Overridden constants are defined
+    let compiler = new_compiler(&evm_version, &["L2"]);
+    assert_eq!(constants(&compiler), vec!["GAS_TOKEN", "BRIDGE", "L2"]);
+    assert!(compiler.execute().unwrap()[0].bytecode.ends_with("6002"));
+    let compiler = new_compiler(&evm_version, &["L2", "DEBUG"]);
+    assert!(compiler.execute().unwrap()[0].bytecode.ends_with("600260dd50"));
+}
+
+#[test]
+fn test_conditionals_across_files() {
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[("main.huff", "#include \"./lib.huff\"\n#endif"), ("lib.huff", "#ifdef DEBUG\n")],
+        None,
+    );
+
+    // Conditional branches can't span across files
+    match compiler.grab_contracts().unwrap_err().as_ref() {
+        CompilerError::ParserError(e) => {
+            assert_eq!(e.kind, ParserErrorKind::UnmatchedConditional(TokenKind::EndIf));
+            assert_eq!(e.spans.0[0].file.as_ref().unwrap().path, "main.huff");
+        }
+        e => panic!("Unexpected error: {e:?}"),
+    }
+}
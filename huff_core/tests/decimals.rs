@@ -0,0 +1,51 @@
+use huff_utils::prelude::*;
+
+mod common;
+
+#[test]
+fn test_decimal_literals() {
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[(
+            "main.huff",
+            r#"
+        #define constant FEE = 0.003 ether
+
+        #define macro MAIN() = takes (0) returns (0) {
+            [FEE] 1_000 1e3 push2 256 0
+        }
+        "#,
+        )],
+        None,
+    );
+    let artifacts = compiler.execute().unwrap();
+    assert!(artifacts[0].bytecode.ends_with("660aa87bee5380006103e86103e86101005f"));
+}
+
+#[test]
+fn test_decimal_literal_overflow() {
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[(
+            "main.huff",
+            r#"
+        #define macro MAIN() = takes (0) returns (0) {
+            1e80
+        }
+        "#,
+        )],
+        None,
+    );
+
+    // Lexical errors are reported against the file they occur in
+    match compiler.grab_contracts().unwrap_err().as_ref() {
+        CompilerError::LexicalError(e) => {
+            assert_eq!(e.kind, LexicalErrorKind::NumberOverflow(String::from("1e80")));
+            assert_eq!(e.span.file.as_ref().unwrap().path, "main.huff");
+        }
+        e => panic!("Unexpected error: {e:?}"),
+    }
+    assert!(compiler.execute().is_err());
+}
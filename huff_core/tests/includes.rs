@@ -0,0 +1,149 @@
+use huff_core::Compiler;
+use huff_utils::{file_provider::*, prelude::*};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+mod common;
+
+const COMMON: &str = r#"
+#define constant OWNER_SLOT = 0x00
+#define function owner() view returns (address)
+"#;
+
+#[test]
+fn test_diamond_includes() {
+    let main = r#"
+    #include "./lib/Ownable.huff"
+    #include "./lib/Pausable.huff"
+
+    #define macro MAIN() = takes (0) returns (0) {
+        [OWNER_SLOT] sload
+    }
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[
+            ("main.huff", main),
+            ("lib/Ownable.huff", "#include \"./Common.huff\""),
+            ("lib/Pausable.huff", "#include \"../lib/./Common.huff\""),
+            ("lib/Common.huff", COMMON),
+        ],
+        None,
+    );
+
+    // A file included through different paths is only included once
+    let contract = &compiler.grab_contracts().unwrap()[0];
+    assert_eq!(contract.constants.lock().unwrap().len(), 1);
+    assert_eq!(contract.functions.len(), 1);
+    assert!(compiler.execute().unwrap()[0].bytecode.ends_with("5f54"));
+}
+
+/// A file provider recording the paths it reads
+#[derive(Debug)]
+struct RecordingFileProvider {
+    provider: InMemoryFileProvider,
+    reads: Mutex<Vec<String>>,
+}
+
+impl<'a> FileProvider<'a> for RecordingFileProvider {
+    fn read_file(&self, pb: PathBuf) -> Result<Arc<FileSource>, CompilerError> {
+        self.reads.lock().unwrap().push(FileSource::canonical_path(&pb.to_string_lossy()));
+        self.provider.read_file(pb)
+    }
+
+    fn transform_paths(&self, sources: &[String]) -> Result<Vec<PathBuf>, CompilerError> {
+        self.provider.transform_paths(sources)
+    }
+}
+
+#[test]
+fn test_shared_includes_are_read_once() {
+    let sources = [
+        ("lib/Ownable.huff", "#include \"./Common.huff\""),
+        ("lib/Pausable.huff", "#include \"../lib/./Common.huff\""),
+        ("lib/Common.huff", COMMON),
+    ];
+    let provider = Arc::new(RecordingFileProvider {
+        provider: InMemoryFileProvider::new(
+            sources.iter().map(|(p, s)| (p.to_string(), s.to_string())).collect::<HashMap<_, _>>(),
+        ),
+        reads: Mutex::new(vec![]),
+    });
+    let main = Arc::new(FileSource {
+        path: "main.huff".to_string(),
+        source: Some(
+            "#include \"./lib/Ownable.huff\"\n#include \"./lib/Pausable.huff\"".to_string(),
+        ),
+        ..Default::default()
+    });
+    let fs = Compiler::recurse_deps(main, &Remapper::new("./"), provider.clone()).unwrap();
+
+    // The shared file is only read once
+    let mut reads = provider.reads.lock().unwrap().clone();
+    reads.sort();
+    assert_eq!(reads, vec!["lib/Common.huff", "lib/Ownable.huff", "lib/Pausable.huff"]);
+
+    // But each include still gets its own copy of it
+    let dependencies = fs.dependencies.as_ref().unwrap();
+    let ownable = &dependencies[0].dependencies.as_ref().unwrap()[0];
+    let pausable = &dependencies[1].dependencies.as_ref().unwrap()[0];
+    assert!(!Arc::ptr_eq(ownable, pausable));
+    assert_eq!(ownable.source, pausable.source);
+}
+
+#[test]
+fn test_includes_into_namespaces() {
+    let main = r#"
+    #include "./Common.huff"
+    #include "./Common.huff" as A
+    #include "./Wrapper.huff" as A
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[
+            ("main.huff", main),
+            ("Wrapper.huff", "#include \"./Common.huff\""),
+            ("Common.huff", COMMON),
+        ],
+        None,
+    );
+
+    // A file is included once into each namespace
+    let contract = &compiler.grab_contracts().unwrap()[0];
+    let constants = contract.constants.lock().unwrap();
+    let names = constants.iter().map(|c| c.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, vec!["OWNER_SLOT", "A::OWNER_SLOT"]);
+}
+
+#[test]
+fn test_include_cycle() {
+    let evm_version = EVMVersion::default();
+    let cycle = common::compiler(
+        &evm_version,
+        &[
+            ("main.huff", "#include \"./a.huff\""),
+            ("a.huff", "#include \"./lib/b.huff\""),
+            ("lib/b.huff", "#include \"../a.huff\""),
+        ],
+        None,
+    );
+
+    // The chain of includes forming the cycle is reported
+    match cycle.grab_contracts().unwrap_err().as_ref() {
+        CompilerError::IncludeCycle(chain) => {
+            assert_eq!(chain, &vec!["a.huff", "lib/b.huff", "a.huff"]);
+        }
+        e => panic!("Unexpected error: {e:?}"),
+    }
+
+    let self_include =
+        common::compiler(&evm_version, &[("main.huff", "#include \"./main.huff\"")], None);
+    let error = self_include.grab_contracts().unwrap_err();
+    assert_eq!(*error, CompilerError::IncludeCycle(vec!["main.huff".into(), "main.huff".into()]));
+    assert!(error.to_string().contains("Include Cycle: main.huff -> main.huff"));
+}
@@ -0,0 +1,226 @@
+use huff_utils::prelude::*;
+
+mod common;
+
+const OWNABLE: &str = r#"
+#include "./Auth.huff"
+
+#define constant OWNER_SLOT = 0x00
+
+#define macro ONLY_OWNER() = takes (0) returns (0) {
+    [OWNER_SLOT] sload AUTH()
+}
+"#;
+
+const AUTH: &str = r#"
+#define macro AUTH() = takes (1) returns (0) {
+    caller eq authorized jumpi
+    0x00 dup1 revert
+    authorized:
+}
+"#;
+
+const PAUSABLE: &str = r#"
+#define constant OWNER_SLOT = 0x01
+
+#define macro ONLY_OWNER() = takes (0) returns (0) {
+    [OWNER_SLOT] sload pop
+}
+"#;
+
+#[test]
+fn test_namespaced_imports() {
+    let main = r#"
+    #include "./Ownable.huff" as Ownable
+    #include "./Pausable.huff" as Pausable
+
+    #define macro MAIN() = takes (0) returns (0) {
+        Ownable::ONLY_OWNER()
+        Pausable::ONLY_OWNER()
+        [Ownable::OWNER_SLOT] [Pausable::OWNER_SLOT]
+    }
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[
+            ("main.huff", main),
+            ("Ownable.huff", OWNABLE),
+            ("Auth.huff", AUTH),
+            ("Pausable.huff", PAUSABLE),
+        ],
+        None,
+    );
+
+    // Definitions are qualified with the namespace of their file. Files included without an
+    // alias share the namespace of the file including them.
+    let contract = &compiler.grab_contracts().unwrap()[0];
+    let mut macros = contract.macros.iter().map(|m| m.name.as_str()).collect::<Vec<&str>>();
+    macros.sort();
+    assert_eq!(
+        macros,
+        vec!["MAIN", "Ownable::AUTH", "Ownable::ONLY_OWNER", "Pausable::ONLY_OWNER"]
+    );
+    let constants = contract.constants.lock().unwrap();
+    assert!(constants.iter().any(|c| c.name == "Ownable::OWNER_SLOT"));
+    assert!(constants.iter().any(|c| c.name == "Pausable::OWNER_SLOT"));
+    drop(constants);
+
+    // References within a namespace resolve to the namespace's definitions
+    let only_owner = contract.find_macro_by_name("Ownable::ONLY_OWNER").unwrap();
+    assert_eq!(only_owner.statements[0].ty, StatementType::Constant("Ownable::OWNER_SLOT".into()));
+    assert!(matches!(
+        &only_owner.statements[2].ty,
+        StatementType::MacroInvocation(mi) if mi.macro_name == "Ownable::AUTH"
+    ));
+
+    let artifacts = compiler.execute().unwrap();
+    assert!(artifacts[0].bytecode.ends_with("5f54331461000b575f80fd5b600154505f6001"));
+}
+
+#[test]
+fn test_file_included_into_multiple_namespaces() {
+    let main = r#"
+    #include "./Ownable.huff" as Ownable
+    #include "./Admin.huff" as Admin
+
+    #define macro MAIN() = takes (0) returns (0) {
+        0x01 Ownable::AUTH()
+        0x02 Admin::AUTH()
+    }
+    "#;
+    let admin = r#"
+    #include "./Auth.huff"
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[
+            ("main.huff", main),
+            ("Ownable.huff", OWNABLE),
+            ("Admin.huff", admin),
+            ("Auth.huff", AUTH),
+        ],
+        None,
+    );
+
+    // Each include of a file defines its macros in the namespace it was included into
+    let contract = &compiler.grab_contracts().unwrap()[0];
+    assert!(contract.find_macro_by_name("Ownable::AUTH").is_some());
+    assert!(contract.find_macro_by_name("Admin::AUTH").is_some());
+    assert!(compiler.execute().is_ok());
+}
+
+#[test]
+fn test_unqualified_reference_to_namespaced_definition() {
+    let main = r#"
+    #include "./Pausable.huff" as Pausable
+
+    #define macro MAIN() = takes (0) returns (0) {
+        ONLY_OWNER()
+    }
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler =
+        common::compiler(&evm_version, &[("main.huff", main), ("Pausable.huff", PAUSABLE)], None);
+
+    match compiler.execute().unwrap_err().as_ref() {
+        CompilerError::FailedCompiles(errors) => match &errors[0] {
+            CompilerError::CodegenError(e) => assert_eq!(
+                e.kind,
+                CodegenErrorKind::InvalidMacroInvocation("ONLY_OWNER".to_string())
+            ),
+            e => panic!("Unexpected error: {e:?}"),
+        },
+        e => panic!("Unexpected error: {e:?}"),
+    }
+}
+
+#[test]
+fn test_duplicate_definitions_across_files() {
+    let main = r#"
+    #include "./Ownable.huff"
+    #include "./Guard.huff"
+
+    #define macro MAIN() = takes (0) returns (0) {}
+    "#;
+    let guard = r#"
+    #define macro ONLY_OWNER() = takes (0) returns (0) {}
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[
+            ("main.huff", main),
+            ("Ownable.huff", OWNABLE),
+            ("Auth.huff", AUTH),
+            ("Guard.huff", guard),
+        ],
+        None,
+    );
+
+    match compiler.grab_contracts().unwrap_err().as_ref() {
+        CompilerError::ParserError(e) => {
+            assert_eq!(e.kind, ParserErrorKind::DuplicateMacro("ONLY_OWNER".to_string()));
+            assert_eq!(e.spans.0[1].file.as_ref().unwrap().path, "Guard.huff");
+        }
+        e => panic!("Unexpected error: {e:?}"),
+    }
+}
+
+#[test]
+fn test_invalid_namespace() {
+    let main = r#"
+    #include "./Pausable.huff" as Lib::Pausable
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler =
+        common::compiler(&evm_version, &[("main.huff", main), ("Pausable.huff", PAUSABLE)], None);
+
+    match compiler.grab_contracts().unwrap_err().as_ref() {
+        CompilerError::ParserError(e) => assert_eq!(
+            e.kind,
+            ParserErrorKind::InvalidName(TokenKind::Ident("Lib::Pausable".to_string()))
+        ),
+        e => panic!("Unexpected error: {e:?}"),
+    }
+}
+
+#[test]
+fn test_duplicate_constants_across_files() {
+    let main = r#"
+    #include "./Ownable.huff"
+    #include "./Pausable.huff"
+    "#;
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[
+            ("main.huff", main),
+            ("Ownable.huff", OWNABLE),
+            ("Auth.huff", AUTH),
+            ("Pausable.huff", PAUSABLE),
+        ],
+        None,
+    );
+
+    // Both definitions are reported, along with the files they are defined in
+    let error = compiler.grab_contracts().unwrap_err();
+    match error.as_ref() {
+        CompilerError::ParserError(e) => {
+            assert_eq!(e.kind, ParserErrorKind::DuplicateConstant("OWNER_SLOT".to_string()));
+            let files = e
+                .spans
+                .0
+                .iter()
+                .map(|s| s.file.as_ref().unwrap().path.as_str())
+                .collect::<Vec<&str>>();
+            assert_eq!(files, vec!["Ownable.huff", "Pausable.huff"]);
+        }
+        e => panic!("Unexpected error: {e:?}"),
+    }
+    let message = error.to_string();
+    assert!(message.contains("-> Ownable.huff:"));
+    assert!(message.contains("-> Pausable.huff:"));
+    assert!(message.contains("#define constant OWNER_SLOT = 0x01"));
+}
@@ -292,3 +292,37 @@ fn test_invalid_single_arg() {
         }
     }
 }
+
+#[test]
+fn test_invalid_decorator_flag_arg_size() {
+    let source = r#"
+    #[caller(0x010000000000000000000000000000000000000000)]
+    #define test MY_TEST() = takes(0) returns(0) {}
+    "#;
+    let arg_start = source.find("0x01").unwrap() + 2;
+    let arg_end = arg_start + "010000000000000000000000000000000000000000".len() - 1;
+
+    let full_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(full_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, Some("".to_string()));
+
+    match parser.parse() {
+        Ok(_) => panic!("moose"),
+        Err(e) => {
+            assert_eq!(
+                e,
+                ParserError {
+                    kind: ParserErrorKind::InvalidDecoratorFlagArg(TokenKind::Literal(
+                        str_to_bytes32("010000000000000000000000000000000000000000")
+                    )),
+                    hint: Some(
+                        "Literal for decorator flag caller must fit in 20 bytes".to_string()
+                    ),
+                    spans: AstSpan(vec![Span { start: arg_start, end: arg_end, file: None }]),
+                    cursor: 5,
+                }
+            )
+        }
+    }
+}
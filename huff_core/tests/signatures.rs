@@ -0,0 +1,110 @@
+use huff_utils::prelude::*;
+
+mod common;
+
+#[test]
+fn test_canonical_signatures() {
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(
+        &evm_version,
+        &[(
+            "main.huff",
+            r#"
+        #define function transfer(address,uint256) nonpayable returns ()
+        #define event Transfer(address indexed, address indexed, uint256)
+        #define error Unauthorized(address)
+
+        #define macro MAIN() = takes (0) returns (0) {
+            __FUNC_SIG("transfer(address to, uint amount)")
+            __FUNC_SIG("transfer(address,uint)")
+            __FUNC_SIG(transfer)
+            __EVENT_HASH("Transfer(address indexed from, address indexed to, uint value)")
+            __ERROR("Unauthorized(address account)")
+        }
+        "#,
+        )],
+        None,
+    );
+    let artifacts = compiler.execute().unwrap();
+    assert!(artifacts[0].runtime.starts_with(
+        "63a9059cbb63a9059cbb63a9059cbb\
+         7fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef\
+         638e4a23d6"
+    ));
+
+    // Every signature matches a definition
+    assert!(compiler.warnings.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_undefined_signatures() {
+    let source = r#"
+        #define function transfer(address,uint256) nonpayable returns ()
+        #define error Unauthorized(address)
+
+        #define macro MAIN() = takes (0) returns (0) {
+            __FUNC_SIG("Unauthorized(address)")
+            __FUNC_SIG("tranfer(address,uint256)")
+            __FUNC_SIG(tranfer)
+            label:
+                __EVENT_HASH("Transfer(address,address,uint)")
+                __ERROR("Unauthorized(uint)")
+        }
+        "#;
+    let evm_version = EVMVersion::default();
+    let compiler = common::compiler(&evm_version, &[("main.huff", source)], None);
+
+    // Undefined signatures still compile, with a warning for each, and bare names are hashed as is
+    let artifacts = compiler.execute().unwrap();
+    let mut selector = [0u8; 4];
+    hash_bytes(&mut selector, &"tranfer".to_string());
+    let push = selector.iter().fold("63".to_string(), |acc, b| format!("{acc}{b:02x}"));
+    assert!(artifacts[0].runtime.contains(&push));
+    let warnings = compiler.warnings.lock().unwrap();
+    let expected = [
+        ("__FUNC_SIG", "tranfer(address,uint256)", "__FUNC_SIG(\"tranfer"),
+        ("__FUNC_SIG", "tranfer", "__FUNC_SIG(tranfer"),
+        ("__EVENT_HASH", "Transfer(address,address,uint256)", "__EVENT_HASH"),
+        ("__ERROR", "Unauthorized(uint256)", "__ERROR"),
+    ];
+    assert_eq!(warnings.len(), expected.len());
+    for (warning, (builtin, signature, at)) in warnings.iter().zip(expected) {
+        match warning {
+            CompilerError::CodegenError(e) => {
+                assert_eq!(
+                    e.kind,
+                    CodegenErrorKind::UndefinedSignature(
+                        builtin.to_string(),
+                        signature.to_string()
+                    )
+                );
+                assert_eq!(e.span.0[0].start, source.find(at).unwrap());
+                assert_eq!(e.span.0[0].file.as_ref().unwrap().path, "main.huff");
+            }
+            e => panic!("Expected a codegen warning, got {e:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_invalid_signatures() {
+    let cases = [
+        ("__FUNC_SIG(\"transfer(adress,uint256)\")", "transfer(adress,uint256)"),
+        ("__EVENT_HASH(\"Transfer(address,address,uint7)\")", "Transfer(address,address,uint7)"),
+    ];
+    let evm_version = EVMVersion::default();
+    for (statement, signature) in cases {
+        let source =
+            format!("#define macro MAIN() = takes (0) returns (0) {{\n    {statement}\n}}");
+        let compiler = common::compiler(&evm_version, &[("main.huff", &source)], None);
+        match compiler.execute().unwrap_err().as_ref() {
+            CompilerError::FailedCompiles(v) => match &v[0] {
+                CompilerError::CodegenError(e) => {
+                    assert_eq!(e.kind, CodegenErrorKind::InvalidSignature(signature.to_string()))
+                }
+                e => panic!("Expected a codegen error, got {e:?}"),
+            },
+            e => panic!("Expected failed compiles, got {e:?}"),
+        }
+    }
+}
@@ -73,6 +73,14 @@ impl<'a> Lexer<'a> {
         self.chars.peek().map(|(c, _)| *c)
     }
 
+    /// Checks if the next characters are a `::` namespace separator followed by a name
+    fn peek_qualifier(&self) -> bool {
+        let mut chars = self.chars.clone().map(|(c, _)| c);
+        chars.next() == Some(':') &&
+            chars.next() == Some(':') &&
+            chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+    }
+
     fn next_token(&mut self) -> TokenResult {
         if let Some(ch) = self.consume() {
             let token = match ch {
@@ -139,7 +147,14 @@ impl<'a> Lexer<'a> {
 
                     let mut found_kind: Option<TokenKind> = None;
 
-                    let keys = [TokenKind::Define, TokenKind::Include];
+                    let keys = [
+                        TokenKind::Define,
+                        TokenKind::Include,
+                        TokenKind::IfDef,
+                        TokenKind::IfNDef,
+                        TokenKind::Else,
+                        TokenKind::EndIf,
+                    ];
                     for kind in keys.into_iter() {
                         let key = kind.to_string();
                         let peeked = word.clone();
@@ -168,9 +183,19 @@ impl<'a> Lexer<'a> {
                 }
                 // Alphabetical characters
                 ch if ch.is_alphabetic() || ch.eq(&'_') => {
-                    let (word, start, mut end) =
+                    let (mut word, start, mut end) =
                         self.eat_while(Some(ch), |c| c.is_alphanumeric() || c == '_');
 
+                    // Qualified names of namespaced definitions, e.g. `Ownable::ONLY_OWNER`
+                    while self.peek_qualifier() {
+                        self.consume();
+                        self.consume();
+                        let (name, _, name_end) =
+                            self.eat_while(None, |c| c.is_alphanumeric() || c == '_');
+                        word = format!("{word}::{name}");
+                        end = name_end;
+                    }
+
                     let mut found_kind: Option<TokenKind> = None;
                     let keys = [
                         TokenKind::Macro,
@@ -478,6 +503,12 @@ impl<'a> Lexer<'a> {
     }
 
     fn eat_digit(&mut self, initial_char: char) -> TokenResult {
+        // Numbers in macro bodies and constants are values, which may be written in decimal or
+        // scientific notation, and with a unit
+        if matches!(self.context, Context::MacroBody | Context::MacroArgs | Context::Constant) {
+            return self.eat_decimal_literal(initial_char)
+        }
+
         let (integer_str, start, end) =
             self.eat_while(Some(initial_char), |ch| ch.is_ascii_digit());
 
@@ -488,6 +519,46 @@ impl<'a> Lexer<'a> {
         Ok(Token { kind: integer_token, span })
     }
 
+    fn eat_decimal_literal(&mut self, initial_char: char) -> TokenResult {
+        let (mut literal, start, mut end) = self.eat_while(Some(initial_char), |ch| {
+            ch.is_ascii_alphanumeric() || ch == '_' || ch == '.'
+        });
+
+        // The unit follows the number after a space, e.g. `1 ether`
+        if let Some((unit, len)) = self.peek_unit() {
+            (0..len).for_each(|_| {
+                self.consume();
+            });
+            literal = format!("{literal} {unit}");
+            end = self.position;
+        }
+
+        let span = Span { start: start as usize, end: end as usize, file: None };
+        match decimal_to_bytes32(&literal) {
+            Ok(value) => Ok(Token { kind: TokenKind::Literal(value), span }),
+            Err(kind) => {
+                tracing::error!(target: "lexer", "INVALID DECIMAL LITERAL '{}'", literal);
+                Err(LexicalError::new(kind, span))
+            }
+        }
+    }
+
+    /// Peeks past the spaces following a number for a unit, returning the unit and the number of
+    /// characters up to the end of it
+    fn peek_unit(&self) -> Option<(&'static str, usize)> {
+        let mut chars = self.chars.clone().map(|(c, _)| c).peekable();
+        let mut len = 0;
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {
+            len += 1;
+        }
+        let word: String =
+            std::iter::from_fn(|| chars.next_if(|c| c.is_alphanumeric() || *c == '_')).collect();
+        UNITS
+            .iter()
+            .find(|(unit, _)| len > 0 && *unit == word)
+            .map(|(unit, _)| (*unit, len + word.len()))
+    }
+
     fn eat_hex_digit(&mut self, initial_char: char) -> TokenResult {
         let (integer_str, mut start, end) =
             self.eat_while(Some(initial_char), |ch| ch.is_ascii_hexdigit() | (ch == 'x'));
@@ -173,3 +173,41 @@ fn include_with_string_single_quote() {
     // We should have reached EOF now
     assert!(lexer.eof);
 }
+
+#[test]
+fn lex_namespaced_import() {
+    let source = "#include \"./Ownable.huff\" as Ownable";
+    let lexer = Lexer::new(source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|x| !matches!(x, TokenKind::Whitespace))
+        .collect::<Vec<TokenKind>>();
+
+    assert_eq!(
+        tokens,
+        vec![
+            TokenKind::Include,
+            TokenKind::Str("./Ownable.huff".to_string()),
+            TokenKind::Ident("as".to_string()),
+            TokenKind::Ident("Ownable".to_string()),
+            TokenKind::Eof
+        ]
+    );
+    assert_eq!(Lexer::lex_imports(source), vec!["./Ownable.huff"]);
+}
+
+#[test]
+fn lex_qualified_identifiers() {
+    let source =
+        "#define macro MAIN() = takes(0) returns(0) {\n Ownable::ONLY_OWNER() [Lib::Math::ONE]\n}";
+    let lexer = Lexer::new(source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|x| !matches!(x, TokenKind::Whitespace))
+        .collect::<Vec<TokenKind>>();
+
+    assert!(tokens.contains(&TokenKind::Ident("Ownable::ONLY_OWNER".to_string())));
+    assert!(tokens.contains(&TokenKind::Ident("Lib::Math::ONE".to_string())));
+}
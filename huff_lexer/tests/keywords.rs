@@ -668,3 +668,28 @@ fn parses_takes_keyword_arbitrary_whitespace() {
     // We covered the whole source
     assert!(lexer.eof);
 }
+
+#[test]
+fn parses_conditional_directives() {
+    let source = "#ifdef DEBUG\n#else\n#endif\n#ifndef DEBUG\n#endif";
+    let lexer = Lexer::new(source);
+    let tokens = lexer
+        .into_iter()
+        .map(|x| x.unwrap().kind)
+        .filter(|x| !matches!(x, TokenKind::Whitespace))
+        .collect::<Vec<TokenKind>>();
+
+    assert_eq!(
+        tokens,
+        vec![
+            TokenKind::IfDef,
+            TokenKind::Ident("DEBUG".to_string()),
+            TokenKind::Else,
+            TokenKind::EndIf,
+            TokenKind::IfNDef,
+            TokenKind::Ident("DEBUG".to_string()),
+            TokenKind::EndIf,
+            TokenKind::Eof
+        ]
+    );
+}
@@ -32,3 +32,59 @@ fn lexes_large_numbers() {
     // We covered the whole source
     assert!(lexer.eof);
 }
+
+#[test]
+fn lexes_decimal_literals() {
+    let source = r#"
+    #define constant FEE = 3_000
+    #define macro MAIN() = takes (0) returns (0) {
+        1e18 1.5 gwei 0 FOO(2 ether)
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let literals = lexer
+        .into_iter()
+        .map(|x| x.unwrap())
+        .filter(|t| matches!(t.kind, TokenKind::Literal(_)))
+        .map(|t| (t.kind, &source[t.span.start..=t.span.end]))
+        .collect::<Vec<(TokenKind, &str)>>();
+
+    // Numbers in constants and macro bodies are values
+    assert_eq!(
+        literals,
+        vec![
+            (TokenKind::Literal(str_to_bytes32("0bb8")), "3_000"),
+            (TokenKind::Literal(str_to_bytes32("0de0b6b3a7640000")), "1e18"),
+            (TokenKind::Literal(str_to_bytes32("59682f00")), "1.5 gwei"),
+            (TokenKind::Literal(str_to_bytes32("00")), "0"),
+            (TokenKind::Literal(str_to_bytes32("1bc16d674ec80000")), "2 ether"),
+        ]
+    );
+}
+
+#[test]
+fn lexes_invalid_decimal_literals() {
+    let errors = [
+        ("1_", LexicalErrorKind::InvalidNumber(String::from("1_"))),
+        ("1__0", LexicalErrorKind::InvalidNumber(String::from("1__0"))),
+        ("1e", LexicalErrorKind::InvalidNumber(String::from("1e"))),
+        ("1.5", LexicalErrorKind::InvalidNumber(String::from("1.5"))),
+        ("12abc", LexicalErrorKind::InvalidNumber(String::from("12abc"))),
+        ("1.0000000001 gwei", LexicalErrorKind::InvalidNumber(String::from("1.0000000001 gwei"))),
+        ("1e78", LexicalErrorKind::NumberOverflow(String::from("1e78"))),
+        ("1e100 ether", LexicalErrorKind::NumberOverflow(String::from("1e100 ether"))),
+        (
+            "1e18446744073709551610 ether",
+            LexicalErrorKind::NumberOverflow(String::from("1e18446744073709551610 ether")),
+        ),
+    ];
+    for (number, kind) in errors {
+        let source = &format!("#define constant VALUE = {number}");
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source.source);
+        let error = lexer.into_iter().find_map(|x| x.err()).unwrap();
+        assert_eq!(error.kind, kind);
+        assert_eq!(&source[error.span.start..=error.span.end], number);
+    }
+}
@@ -13,6 +13,7 @@ use huff_utils::{
     types::*,
 };
 use regex::Regex;
+use std::{collections::BTreeSet, sync::Arc};
 
 /// The Parser
 #[derive(Debug, Clone)]
@@ -29,6 +30,12 @@ pub struct Parser {
     pub spans: Vec<Span>,
     /// Our remapper
     pub remapper: files::Remapper,
+    /// The namespaces of included files. A file included into more than one namespace is parsed
+    /// once per namespace.
+    pub namespaces: Vec<(Arc<files::FileSource>, String)>,
+    /// Constants defined outside of the source, such as overrides, which conditional directives
+    /// are evaluated against alongside the source's constants
+    pub defined_constants: BTreeSet<String>,
 }
 
 impl Parser {
@@ -36,7 +43,16 @@ impl Parser {
     pub fn new(tokens: Vec<Token>, base: Option<String>) -> Self {
         let initial_token = tokens.first().unwrap().clone();
         let remapper = files::Remapper::new("./");
-        Self { tokens, cursor: 0, current_token: initial_token, base, spans: vec![], remapper }
+        Self {
+            tokens,
+            cursor: 0,
+            current_token: initial_token,
+            base,
+            spans: vec![],
+            remapper,
+            namespaces: vec![],
+            defined_constants: BTreeSet::new(),
+        }
     }
 
     /// Resets the current token and cursor to the first token in the parser's token vec
@@ -53,6 +69,10 @@ impl Parser {
         self.tokens
             .retain(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment(_)));
 
+        // Then the tokens of inactive conditional branches, registering the namespaces of the
+        // files included from active ones
+        self.preprocess()?;
+
         // Reset the initial token
         self.reset();
 
@@ -70,8 +90,10 @@ impl Parser {
             }
             // Check for a decorator above a test macro
             else if self.check(TokenKind::Pound) {
-                let m = self.parse_macro()?;
+                let mut m = self.parse_macro()?;
+                m.name = self.qualify(&m.span, &m.name);
                 tracing::info!(target: "parser", "SUCCESSFULLY PARSED MACRO {}", m.name);
+                self.check_duplicate_macro(&contract, &m)?;
                 contract.macros.push(m);
             }
             // Check for a defition with the "#define" keyword
@@ -84,31 +106,100 @@ impl Parser {
                     TokenKind::Function => {
                         let func = self.parse_function()?;
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED FUNCTION {}", func.name);
+                        let signature = func.canonical_signature();
+                        self.check_duplicate(
+                            contract
+                                .functions
+                                .iter()
+                                .filter(|f| f.canonical_signature() == signature)
+                                .map(|f| &f.span),
+                            &func.span,
+                            &func.name,
+                            ParserErrorKind::DuplicateFunction(func.name.to_owned()),
+                            "FUNCTION signatures should be unique",
+                        )?;
+                        self.check_selector_collision(
+                            &contract,
+                            &func.signature,
+                            &signature,
+                            &func.span,
+                            &func.name,
+                        )?;
                         contract.functions.push(func);
                     }
                     TokenKind::Event => {
                         let ev = self.parse_event()?;
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED EVENT {}", ev.name);
+                        self.check_duplicate(
+                            contract.events.iter().filter(|e| e.hash == ev.hash).map(|e| &e.span),
+                            &ev.span,
+                            &ev.name,
+                            ParserErrorKind::DuplicateEvent(ev.name.to_owned()),
+                            "EVENT signatures should be unique",
+                        )?;
                         contract.events.push(ev);
                     }
                     TokenKind::Constant => {
-                        let c = self.parse_constant()?;
+                        let mut c = self.parse_constant()?;
+                        c.name = self.qualify(&c.span, &c.name);
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED CONSTANT {}", c.name);
+                        self.check_duplicate(
+                            contract
+                                .constants
+                                .lock()
+                                .unwrap()
+                                .iter()
+                                .filter(|d| d.name == c.name)
+                                .map(|d| &d.span),
+                            &c.span,
+                            &c.name,
+                            ParserErrorKind::DuplicateConstant(c.name.to_owned()),
+                            "CONSTANT names should be unique",
+                        )?;
                         contract.constants.lock().unwrap().push(c);
                     }
                     TokenKind::Error => {
                         let e = self.parse_custom_error()?;
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED ERROR {}", e.name);
+                        let signature = e.canonical_signature();
+                        self.check_duplicate(
+                            contract
+                                .errors
+                                .iter()
+                                .filter(|d| d.canonical_signature() == signature)
+                                .map(|d| &d.span),
+                            &e.span,
+                            &e.name,
+                            ParserErrorKind::DuplicateError(e.name.to_owned()),
+                            "ERROR signatures should be unique",
+                        )?;
+                        self.check_selector_collision(
+                            &contract,
+                            &e.selector,
+                            &signature,
+                            &e.span,
+                            &e.name,
+                        )?;
                         contract.errors.push(e);
                     }
                     TokenKind::Macro | TokenKind::Fn | TokenKind::Test => {
-                        let m = self.parse_macro()?;
+                        let mut m = self.parse_macro()?;
+                        m.name = self.qualify(&m.span, &m.name);
                         tracing::info!(target: "parser", "SUCCESSFULLY PARSED MACRO {}", m.name);
                         self.check_duplicate_macro(&contract, &m)?;
                         contract.macros.push(m);
                     }
                     TokenKind::JumpTable | TokenKind::JumpTablePacked | TokenKind::CodeTable => {
-                        contract.tables.push(self.parse_table()?);
+                        let mut t = self.parse_table()?;
+                        t.name = self.qualify(&t.span, &t.name);
+                        self.check_duplicate(
+                            contract.tables.iter().filter(|d| d.name == t.name).map(|d| &d.span),
+                            &t.span,
+                            &t.name,
+                            ParserErrorKind::DuplicateTable(t.name.to_owned()),
+                            "TABLE names should be unique",
+                        )?;
+                        contract.tables.push(t);
                     }
                     _ => {
                         tracing::error!(
@@ -139,10 +230,13 @@ impl Parser {
             }
         }
 
+        // Resolve references to namespaced definitions
+        self.resolve_namespaces(&mut contract);
+
         Ok(contract)
     }
 
-    /// Parses Contract Imports
+    /// Parses Contract Imports, with an optional namespace alias: `#include "./A.huff" as A`
     pub fn parse_imports(&mut self) -> Result<FilePath, ParserError> {
         // First token should be keyword "#include"
         self.match_kind(TokenKind::Include)?;
@@ -165,9 +259,233 @@ impl Parser {
             }
         };
 
+        // Then an optional namespace alias, which is applied when preprocessing
+        if self.current_token.kind == TokenKind::Ident("as".to_string()) {
+            self.consume();
+            match self.current_token.kind.clone() {
+                TokenKind::Ident(name) if !name.contains("::") => self.consume(),
+                kind => {
+                    tracing::error!(target: "parser", "INVALID IMPORT NAMESPACE: {}", kind);
+                    return Err(ParserError {
+                        kind: ParserErrorKind::InvalidName(kind.clone()),
+                        hint: Some(format!("Expected a namespace for the import. Got: \"{kind}\"")),
+                        spans: AstSpan(vec![self.current_token.span.clone()]),
+                        cursor: self.cursor,
+                    })
+                }
+            }
+        }
+
         Ok(std::path::PathBuf::from(p))
     }
 
+    /// Get the namespace of a file, which is empty for files that aren't namespaced
+    pub fn file_namespace(&self, file: &Arc<files::FileSource>) -> String {
+        self.namespaces
+            .iter()
+            .find(|(f, _)| Arc::ptr_eq(f, file))
+            .map(|(_, namespace)| namespace.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the namespace of the file a definition's span belongs to
+    pub fn namespace(&self, span: &AstSpan) -> String {
+        span.0
+            .iter()
+            .find_map(|s| s.file.as_ref())
+            .map(|f| self.file_namespace(f))
+            .unwrap_or_default()
+    }
+
+    /// Qualify the name of a definition with the namespace of the file it is defined in
+    pub fn qualify(&self, span: &AstSpan, name: &str) -> String {
+        match self.namespace(span) {
+            namespace if namespace.is_empty() => name.to_string(),
+            namespace => format!("{namespace}::{name}"),
+        }
+    }
+
+    /// Resolve the references to macros, constants, and tables in namespaced files to the
+    /// qualified names of their definitions. A reference is resolved against the namespace it is
+    /// made in, then each enclosing namespace, and finally the global namespace. References that
+    /// can't be resolved are left as they are, to be reported as missing during codegen.
+    pub fn resolve_namespaces(&self, contract: &mut Contract) {
+        if self.namespaces.iter().all(|(_, n)| n.is_empty()) {
+            return
+        }
+        let macros = contract.macros.iter().map(|m| m.name.clone()).collect::<Vec<String>>();
+        let constants =
+            contract.constants.lock().unwrap().iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        let tables = contract.tables.iter().map(|t| t.name.clone()).collect::<Vec<String>>();
+        let names = Names { macros: &macros, constants: &constants, tables: &tables };
+
+        for m in contract.macros.iter_mut() {
+            let namespace = self.namespace(&m.span);
+            names.resolve_statements(&namespace, &mut m.statements);
+        }
+        for t in contract.tables.iter_mut() {
+            let namespace = self.namespace(&t.span);
+            names.resolve_statements(&namespace, &mut t.statements);
+        }
+    }
+
+    /// Evaluates the `#ifdef`, `#ifndef`, `#else`, and `#endif` conditional directives, removing
+    /// the tokens of inactive branches along with the files included from them, and registers the
+    /// namespace of each remaining included file.
+    ///
+    /// A name is defined if it is one of the [defined_constants](Parser::defined_constants), or a
+    /// constant defined outside of a conditional branch, or within an active branch before the
+    /// directive. A conditional branch must be closed in the file it was opened in.
+    ///
+    /// Files included from inactive branches have already been read and resolved by the time the
+    /// directives are evaluated, so they must exist.
+    pub fn preprocess(&mut self) -> Result<(), ParserError> {
+        let mut defined = self.defined_constants.clone();
+        let mut depth = 0_usize;
+        for window in self.tokens.windows(3) {
+            match &window[0].kind {
+                TokenKind::IfDef | TokenKind::IfNDef => depth += 1,
+                TokenKind::EndIf => depth = depth.saturating_sub(1),
+                TokenKind::Define if depth == 0 => {
+                    if let (TokenKind::Constant, TokenKind::Ident(name)) =
+                        (&window[1].kind, &window[2].kind)
+                    {
+                        defined.insert(name.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The open branches, with whether they and their parent are active, and whether they
+        // have reached their "#else"
+        let mut branches: Vec<(Token, bool, bool, bool)> = vec![];
+        let mut excluded: Vec<Arc<files::FileSource>> = vec![];
+        let mut includes: Vec<(Arc<files::FileSource>, usize)> = vec![];
+        let mut tokens: Vec<Token> = vec![];
+        let mut iter = std::mem::take(&mut self.tokens).into_iter();
+        while let Some(token) = iter.next() {
+            if token.kind == TokenKind::Eof {
+                if let Some((directive, ..)) = branches.pop() {
+                    return Err(unterminated(directive, tokens.len()))
+                }
+                tokens.push(token);
+                break
+            }
+            let file = token.span.file.clone();
+            if file.as_ref().is_some_and(|f| excluded.iter().any(|e| Arc::ptr_eq(e, f))) {
+                continue
+            }
+            if let Some((directive, ..)) = branches.last() {
+                let same_file = match (&directive.span.file, &file) {
+                    (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                    (a, b) => a.is_none() && b.is_none(),
+                };
+                if !same_file {
+                    return Err(unterminated(branches.pop().unwrap().0, tokens.len()))
+                }
+            }
+
+            let active = branches.last().is_none_or(|(_, active, ..)| *active);
+            match &token.kind {
+                TokenKind::IfDef | TokenKind::IfNDef => {
+                    let name = match iter.next() {
+                        Some(Token { kind: TokenKind::Ident(name), .. }) => name,
+                        next => {
+                            let next = next.unwrap_or(token);
+                            return Err(ParserError {
+                                kind: ParserErrorKind::UnexpectedType(next.kind.clone()),
+                                hint: Some(format!(
+                                    "Expected a constant name. Got: \"{}\"",
+                                    next.kind
+                                )),
+                                spans: AstSpan(vec![next.span]),
+                                cursor: tokens.len(),
+                            })
+                        }
+                    };
+                    let branch = defined.contains(&name) == (token.kind == TokenKind::IfDef);
+                    branches.push((token, active && branch, active, false));
+                }
+                TokenKind::Else => match branches.last_mut() {
+                    Some((_, active, parent, seen_else)) if !*seen_else => {
+                        *active = *parent && !*active;
+                        *seen_else = true;
+                    }
+                    _ => return Err(unmatched(token, tokens.len())),
+                },
+                TokenKind::EndIf => {
+                    branches.pop().ok_or_else(|| unmatched(token, tokens.len()))?;
+                }
+                TokenKind::Include => {
+                    // Match the include to the file it includes, by its position in its file
+                    let dependency = file.as_ref().and_then(|file| {
+                        let index = match includes.iter_mut().find(|(f, _)| Arc::ptr_eq(f, file)) {
+                            Some((_, count)) => {
+                                *count += 1;
+                                *count - 1
+                            }
+                            None => {
+                                includes.push((Arc::clone(file), 1));
+                                0
+                            }
+                        };
+                        file.dependencies.as_ref().and_then(|deps| deps.get(index).cloned())
+                    });
+                    let Some(dependency) = dependency else {
+                        if active {
+                            tokens.push(token);
+                        }
+                        continue
+                    };
+                    if !active {
+                        exclude(&dependency, &mut excluded);
+                        continue
+                    }
+
+                    // The included file shares the namespace of the file including it, unless
+                    // aliased. A file is only included once into each namespace.
+                    let parent = file.map(|f| self.file_namespace(&f)).unwrap_or_default();
+                    let namespace = match iter.as_slice() {
+                        [_, r#as, Token { kind: TokenKind::Ident(alias), .. }, ..]
+                            if r#as.kind == TokenKind::Ident("as".to_string()) =>
+                        {
+                            if parent.is_empty() {
+                                alias.clone()
+                            } else {
+                                format!("{parent}::{alias}")
+                            }
+                        }
+                        _ => parent,
+                    };
+                    let path = files::FileSource::canonical_path(&dependency.path);
+                    if self.namespaces.iter().any(|(f, n)| {
+                        *n == namespace && files::FileSource::canonical_path(&f.path) == path
+                    }) {
+                        exclude(&dependency, &mut excluded);
+                    } else {
+                        self.namespaces.push((dependency, namespace));
+                    }
+                    tokens.push(token);
+                }
+                TokenKind::Ident(name) if active => {
+                    if let [.., define, constant] = tokens.as_slice() {
+                        if (&define.kind, &constant.kind) ==
+                            (&TokenKind::Define, &TokenKind::Constant)
+                        {
+                            defined.insert(name.clone());
+                        }
+                    }
+                    tokens.push(token);
+                }
+                _ if active => tokens.push(token),
+                _ => {}
+            }
+        }
+        self.tokens = tokens;
+        Ok(())
+    }
+
     /// Match current token to a type.
     pub fn match_kind(&mut self, kind: TokenKind) -> Result<TokenKind, ParserError> {
         if std::mem::discriminant(&self.current_token.kind) == std::mem::discriminant(&kind) {
@@ -190,22 +508,80 @@ impl Parser {
         std::mem::discriminant(&self.current_token.kind) == std::mem::discriminant(&kind)
     }
 
-    /// Checks if there is a duplicate macro name
+    /// Checks if there is a duplicate macro name, across all included files
     pub fn check_duplicate_macro(
         &self,
         contract: &Contract,
         m: &MacroDefinition,
     ) -> Result<(), ParserError> {
-        if contract.macros.binary_search_by(|_macro| _macro.name.cmp(&m.name)).is_ok() {
-            tracing::error!(target: "parser", "DUPLICATE MACRO NAME FOUND: {}",  m.name);
-            Err(ParserError {
-                kind: ParserErrorKind::DuplicateMacro(m.name.to_owned()),
-                hint: Some("MACRO names should be unique".to_string()),
-                spans: AstSpan(vec![m.span[2].clone()]),
-                cursor: self.cursor,
-            })
-        } else {
-            Ok(())
+        self.check_duplicate(
+            contract.macros.iter().filter(|d| d.name == m.name).map(|d| &d.span),
+            &m.span,
+            &m.name,
+            ParserErrorKind::DuplicateMacro(m.name.to_owned()),
+            "MACRO names should be unique",
+        )
+    }
+
+    /// Checks if a definition duplicates any of the given earlier definitions of the same name or
+    /// signature. The error points at both definitions, which may be in different files.
+    ///
+    /// A file included into more than one namespace defines everything more than once, at the
+    /// same location, which isn't a duplicate.
+    pub fn check_duplicate<'a>(
+        &self,
+        previous: impl IntoIterator<Item = &'a AstSpan>,
+        span: &AstSpan,
+        name: &str,
+        kind: ParserErrorKind,
+        hint: &str,
+    ) -> Result<(), ParserError> {
+        let location = |span: &AstSpan| {
+            span.0
+                .iter()
+                .map(|s| (s.start, s.end, s.file.as_ref().map(|f| f.path.clone())))
+                .collect::<Vec<_>>()
+        };
+        match previous.into_iter().find(|p| location(p) != location(span)) {
+            Some(previous) => {
+                tracing::error!(target: "parser", "DUPLICATE DEFINITION FOUND: {}", name);
+                Err(ParserError {
+                    kind,
+                    hint: Some(hint.to_string()),
+                    spans: AstSpan(vec![name_span(previous, name), name_span(span, name)]),
+                    cursor: self.cursor,
+                })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Checks if a function or error's selector collides with the selector of a different
+    /// function or error defined before it. Functions and errors share a selector space, as a
+    /// revert's data can't be told apart from a call's by its selector.
+    pub fn check_selector_collision(
+        &self,
+        contract: &Contract,
+        selector: &[u8; 4],
+        signature: &str,
+        span: &AstSpan,
+        name: &str,
+    ) -> Result<(), ParserError> {
+        let previous = contract
+            .functions
+            .iter()
+            .map(|f| (f.signature, f.canonical_signature(), &f.span))
+            .chain(contract.errors.iter().map(|e| (e.selector, e.canonical_signature(), &e.span)))
+            .find(|(s, _, previous)| s == selector && *previous != span);
+        match previous {
+            Some((_, previous, previous_span)) => self.check_duplicate(
+                [previous_span],
+                span,
+                name,
+                ParserErrorKind::SelectorCollision(previous, signature.to_string()),
+                "FUNCTION and ERROR selectors should be unique",
+            ),
+            None => Ok(()),
         }
     }
 
@@ -444,8 +820,11 @@ impl Parser {
 
         while !self.check(TokenKind::CloseBracket) {
             if let TokenKind::Ident(s) = self.match_kind(TokenKind::Ident(String::default()))? {
-                // Consume the open parenthesis
-                self.consume();
+                // Flags without arguments may omit the parentheses
+                let has_args = self.check(TokenKind::OpenParen);
+                if has_args {
+                    self.consume();
+                }
 
                 match DecoratorFlag::try_from(&s) {
                     // The calldata flag accepts a single string as an argument
@@ -465,12 +844,42 @@ impl Parser {
                             });
                         }
                     }
-                    // The value flag accepts a single literal as an argument
-                    Ok(DecoratorFlag::Value(_)) => {
+                    // The calldata_abi flag accepts a function signature or name as a string,
+                    // followed by the function's arguments
+                    Ok(DecoratorFlag::CalldataAbi(_, _)) => {
+                        let (signature, args) = self.parse_decorator_args(&s)?;
+                        flags.push(DecoratorFlag::CalldataAbi(signature, args));
+                    }
+                    // The deploy flag accepts the path of a contract as a string, followed by its
+                    // constructor arguments
+                    Ok(DecoratorFlag::Deploy(_, _)) => {
+                        let (path, args) = self.parse_decorator_args(&s)?;
+                        flags.push(DecoratorFlag::Deploy(path, args));
+                    }
+                    // The invariant flag accepts no arguments
+                    Ok(DecoratorFlag::Invariant) => flags.push(DecoratorFlag::Invariant),
+                    // The remaining flags accept a single literal as an argument
+                    Ok(flag) => {
+                        let span = self.current_token.span.clone();
                         if let TokenKind::Literal(l) =
                             self.match_kind(TokenKind::Literal(Literal::default()))?
                         {
-                            flags.push(DecoratorFlag::Value(l));
+                            // Addresses, the chain id and the gas limit must fit their types
+                            if let Some(max_bytes) = flag.max_literal_bytes() {
+                                if l[..32 - max_bytes].iter().any(|b| *b != 0) {
+                                    return Err(ParserError {
+                                        kind: ParserErrorKind::InvalidDecoratorFlagArg(
+                                            TokenKind::Literal(l),
+                                        ),
+                                        hint: Some(format!(
+                                            "Literal for decorator flag {s} must fit in {max_bytes} bytes"
+                                        )),
+                                        spans: AstSpan(vec![span]),
+                                        cursor: self.cursor,
+                                    })
+                                }
+                            }
+                            flags.extend(flag.with_literal(l));
                         } else {
                             return Err(ParserError {
                                 kind: ParserErrorKind::InvalidDecoratorFlagArg(
@@ -494,7 +903,9 @@ impl Parser {
                 }
 
                 // Consume the closing parenthesis
-                self.match_kind(TokenKind::CloseParen)?;
+                if has_args {
+                    self.match_kind(TokenKind::CloseParen)?;
+                }
 
                 // Multiple flags are possible
                 if self.check(TokenKind::Comma) {
@@ -518,6 +929,47 @@ impl Parser {
         Ok(Decorator { flags })
     }
 
+    /// Parses the arguments of a decorator flag that accepts a string followed by a list of
+    /// comma separated strings, literals or numbers.
+    fn parse_decorator_args(&mut self, flag: &str) -> Result<(String, Vec<String>), ParserError> {
+        if let TokenKind::Str(first) = self.match_kind(TokenKind::Str(String::default()))? {
+            let mut args = Vec::default();
+            while self.check(TokenKind::Comma) {
+                self.consume();
+                args.push(match &self.current_token.kind {
+                    TokenKind::Str(a) | TokenKind::Ident(a) => a.clone(),
+                    TokenKind::Literal(l) => {
+                        // Keep the leading zero bytes of the literal, which are significant for
+                        // dynamic `bytes` arguments
+                        let span = &self.current_token.span;
+                        let width = (span.end + 2 - span.start) / 2 * 2;
+                        format!("0x{:0>width$}", bytes32_to_string(l, false))
+                    }
+                    TokenKind::Num(n) => n.to_string(),
+                    kind => {
+                        return Err(ParserError {
+                            kind: ParserErrorKind::InvalidDecoratorFlagArg(kind.clone()),
+                            hint: Some(format!(
+                                "Expected string, literal or number for decorator flag: {flag}"
+                            )),
+                            spans: AstSpan(vec![self.current_token.span.clone()]),
+                            cursor: self.cursor,
+                        })
+                    }
+                });
+                self.consume();
+            }
+            Ok((first, args))
+        } else {
+            Err(ParserError {
+                kind: ParserErrorKind::InvalidDecoratorFlagArg(self.current_token.kind.clone()),
+                hint: Some(format!("Expected string for decorator flag: {flag}")),
+                spans: AstSpan(vec![self.current_token.span.clone()]),
+                cursor: self.cursor,
+            })
+        }
+    }
+
     /// Parses a macro.
     ///
     /// It should parse the following : macro MACRO_NAME(args...) = takes (x) returns (n) {...}
@@ -570,7 +1022,7 @@ impl Parser {
 
     /// Parse the body of a macro.
     ///
-    /// Only HEX, OPCODES, labels, builtins, and MACRO calls should be authorized.
+    /// Only HEX, strings, OPCODES, labels, builtins, and MACRO calls should be authorized.
     pub fn parse_body(&mut self) -> Result<Vec<Statement>, ParserError> {
         let mut statements: Vec<Statement> = Vec::new();
         self.match_kind(TokenKind::OpenBrace)?;
@@ -586,6 +1038,25 @@ impl Parser {
                         span: AstSpan(curr_spans),
                     });
                 }
+                TokenKind::Str(str) => {
+                    // A string literal pushes its bytes, as with the `__BYTES` builtin
+                    let curr_spans = vec![self.current_token.span.clone()];
+                    tracing::info!(target: "parser", "PARSING MACRO BODY: [STRING: {}]", str);
+                    self.consume();
+                    statements.push(Statement {
+                        ty: StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                            kind: BuiltinFunctionKind::Bytes,
+                            args: vec![Argument {
+                                name: Some(str),
+                                arg_type: Some(String::from("string")),
+                                span: AstSpan(curr_spans.clone()),
+                                ..Default::default()
+                            }],
+                            span: AstSpan(curr_spans.clone()),
+                        }),
+                        span: AstSpan(curr_spans),
+                    });
+                }
                 TokenKind::Opcode(o) => {
                     let curr_spans = vec![self.current_token.span.clone()];
                     tracing::info!(target: "parser", "PARSING MACRO BODY: [OPCODE: {}]", o);
@@ -613,7 +1084,7 @@ impl Parser {
                                         )),
                                         spans: AstSpan(curr_spans),
                                         cursor: self.cursor,
-                                    });
+                                    })
                                 }
 
                                 // Otherwise we can push the literal
@@ -758,6 +1229,25 @@ impl Parser {
                         span: AstSpan(curr_spans),
                     });
                 }
+                TokenKind::Str(str) => {
+                    // A string literal pushes its bytes, as with the `__BYTES` builtin
+                    let curr_spans = vec![self.current_token.span.clone()];
+                    tracing::info!(target: "parser", "PARSING LABEL BODY: [STRING: {}]", str);
+                    self.consume();
+                    statements.push(Statement {
+                        ty: StatementType::BuiltinFunctionCall(BuiltinFunctionCall {
+                            kind: BuiltinFunctionKind::Bytes,
+                            args: vec![Argument {
+                                name: Some(str),
+                                arg_type: Some(String::from("string")),
+                                span: AstSpan(curr_spans.clone()),
+                                ..Default::default()
+                            }],
+                            span: AstSpan(curr_spans.clone()),
+                        }),
+                        span: AstSpan(curr_spans),
+                    });
+                }
                 TokenKind::Opcode(o) => {
                     let curr_spans = vec![self.current_token.span.clone()];
                     tracing::info!(target: "parser", "PARSING LABEL BODY: [OPCODE: {}]", o);
@@ -876,7 +1366,7 @@ impl Parser {
                 if let TokenKind::Str(s) = &self.current_token.kind {
                     args.push(Argument {
                         name: Some(s.to_owned()), // Place the string in the "name" field
-                        arg_type: None,
+                        arg_type: Some(String::from("string")),
                         indexed: false,
                         span: AstSpan(vec![self.current_token.span.clone()]),
                         arg_location: None,
@@ -1332,3 +1822,117 @@ impl Parser {
         }
     }
 }
+
+/// An error for a conditional branch that isn't closed
+fn unterminated(directive: Token, cursor: usize) -> ParserError {
+    ParserError {
+        kind: ParserErrorKind::UnterminatedConditional(directive.kind),
+        hint: Some("Expected a matching \"#endif\" in the same file".to_string()),
+        spans: AstSpan(vec![directive.span]),
+        cursor,
+    }
+}
+
+/// An error for an "#else" or "#endif" directive outside of a conditional branch
+fn unmatched(directive: Token, cursor: usize) -> ParserError {
+    ParserError {
+        kind: ParserErrorKind::UnmatchedConditional(directive.kind),
+        hint: Some("Expected a matching \"#ifdef\" or \"#ifndef\"".to_string()),
+        spans: AstSpan(vec![directive.span]),
+        cursor,
+    }
+}
+
+/// Exclude a file, and the files it includes
+fn exclude(file: &Arc<files::FileSource>, excluded: &mut Vec<Arc<files::FileSource>>) {
+    excluded.push(Arc::clone(file));
+    for dependency in file.dependencies.iter().flatten() {
+        exclude(dependency, excluded);
+    }
+}
+
+/// The span of a definition's name, which follows its `#define` and kind keywords, or a decorator
+fn name_span(span: &AstSpan, name: &str) -> Span {
+    let name = name.rsplit("::").next().unwrap_or(name);
+    span.0
+        .iter()
+        .find(|s| {
+            let source = s.file.as_ref().and_then(|f| f.source.as_ref());
+            source.and_then(|src| src.get(s.start..=s.end)) == Some(name)
+        })
+        .or_else(|| span.0.get(2))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// The names of a contract's namespaced definitions, to resolve references against
+struct Names<'a> {
+    macros: &'a [String],
+    constants: &'a [String],
+    tables: &'a [String],
+}
+
+impl Names<'_> {
+    /// Resolve a reference made in a namespace to the name of a definition, if any
+    fn resolve(namespace: &str, name: &str, definitions: &[String]) -> Option<String> {
+        let mut namespace = namespace;
+        loop {
+            let qualified = match namespace {
+                "" => name.to_string(),
+                namespace => format!("{namespace}::{name}"),
+            };
+            if definitions.contains(&qualified) {
+                return Some(qualified)
+            }
+            if namespace.is_empty() {
+                return None
+            }
+            namespace = namespace.rsplit_once("::").map(|(parent, _)| parent).unwrap_or_default();
+        }
+    }
+
+    /// Resolve the references of statements made in a namespace
+    fn resolve_statements(&self, namespace: &str, statements: &mut [Statement]) {
+        for statement in statements.iter_mut() {
+            match &mut statement.ty {
+                StatementType::MacroInvocation(mi) => {
+                    if let Some(name) = Self::resolve(namespace, &mi.macro_name, self.macros) {
+                        mi.macro_name = name;
+                    }
+                    for arg in mi.args.iter_mut() {
+                        if let MacroArg::Ident(ident) = arg {
+                            if let Some(name) = Self::resolve(namespace, ident, self.constants) {
+                                *ident = name;
+                            }
+                        }
+                    }
+                }
+                StatementType::Constant(constant) => {
+                    if let Some(name) = Self::resolve(namespace, constant, self.constants) {
+                        *constant = name;
+                    }
+                }
+                StatementType::Label(label) => self.resolve_statements(namespace, &mut label.inner),
+                StatementType::BuiltinFunctionCall(bf) => {
+                    let (definitions, skip) = match bf.kind {
+                        BuiltinFunctionKind::Codesize => (self.macros, 0),
+                        BuiltinFunctionKind::Tablesize | BuiltinFunctionKind::Tablestart => {
+                            (self.tables, 0)
+                        }
+                        // The first argument of __DISPATCH is its strategy
+                        BuiltinFunctionKind::Dispatch => (self.tables, 1),
+                        _ => continue,
+                    };
+                    for arg in bf.args.iter_mut().skip(skip) {
+                        if let Some(name) =
+                            arg.name.as_ref().and_then(|n| Self::resolve(namespace, n, definitions))
+                        {
+                            arg.name = Some(name);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
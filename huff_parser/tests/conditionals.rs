@@ -0,0 +1,138 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str, defined: &[&str]) -> Result<Contract, ParserError> {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.defined_constants = defined.iter().map(|d| d.to_string()).collect();
+    parser.parse()
+}
+
+/// The statements of the MAIN macro
+fn main_statements(contract: &Contract) -> Vec<StatementType> {
+    contract.find_macro_by_name("MAIN").unwrap().statements.iter().map(|s| s.ty.clone()).collect()
+}
+
+const SOURCE: &str = r#"
+#ifdef L2
+    #define constant GAS_TOKEN = 0x02
+#else
+    #define constant GAS_TOKEN = 0x01
+#endif
+
+#define macro MAIN() = takes (0) returns (0) {
+    [GAS_TOKEN]
+    #ifdef DEBUG
+        0xdd pop
+    #endif
+    #ifndef DEBUG
+        0xee pop
+    #endif
+}
+"#;
+
+#[test]
+fn test_conditional_definitions() {
+    let contract = parse(SOURCE, &[]).unwrap();
+    let constants = contract.constants.lock().unwrap();
+    assert_eq!(constants.len(), 1);
+    assert_eq!(constants[0].value, ConstVal::Literal(str_to_bytes32("01")));
+
+    let contract = parse(SOURCE, &["L2"]).unwrap();
+    let constants = contract.constants.lock().unwrap();
+    assert_eq!(constants.len(), 1);
+    assert_eq!(constants[0].value, ConstVal::Literal(str_to_bytes32("02")));
+}
+
+#[test]
+fn test_conditional_statements() {
+    let contract = parse(SOURCE, &[]).unwrap();
+    assert_eq!(
+        main_statements(&contract),
+        vec![
+            StatementType::Constant("GAS_TOKEN".to_string()),
+            StatementType::Literal(str_to_bytes32("ee")),
+            StatementType::Opcode(Opcode::Pop),
+        ]
+    );
+
+    let contract = parse(SOURCE, &["DEBUG"]).unwrap();
+    assert_eq!(
+        main_statements(&contract),
+        vec![
+            StatementType::Constant("GAS_TOKEN".to_string()),
+            StatementType::Literal(str_to_bytes32("dd")),
+            StatementType::Opcode(Opcode::Pop),
+        ]
+    );
+}
+
+#[test]
+fn test_conditionals_keyed_off_source_constants() {
+    let source = r#"
+    #ifdef DEBUG
+        #define constant VERBOSE = 0x01
+    #endif
+
+    #define macro MAIN() = takes (0) returns (0) {
+        #ifdef VERBOSE
+            0x01
+        #endif
+        #ifdef TESTING
+            0x02
+            #ifndef DEBUG
+                0x03
+            #else
+                0x04
+            #endif
+        #endif
+    }
+
+    #define constant TESTING = 0x00
+    "#;
+
+    // Constants defined outside of conditional branches are defined everywhere
+    let contract = parse(source, &[]).unwrap();
+    assert_eq!(
+        main_statements(&contract),
+        vec![
+            StatementType::Literal(str_to_bytes32("02")),
+            StatementType::Literal(str_to_bytes32("03"))
+        ]
+    );
+
+    // Constants defined within active branches are defined after their definition
+    let contract = parse(source, &["DEBUG"]).unwrap();
+    assert_eq!(
+        main_statements(&contract),
+        vec![
+            StatementType::Literal(str_to_bytes32("01")),
+            StatementType::Literal(str_to_bytes32("02")),
+            StatementType::Literal(str_to_bytes32("04"))
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_conditionals() {
+    let source = "#define macro MAIN() = takes (0) returns (0) {}\n#else\n#endif";
+    let e = parse(source, &[]).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::UnmatchedConditional(TokenKind::Else));
+    assert_eq!(e.spans.0[0].start, source.find("#else").unwrap());
+
+    let source = "#ifdef A\n#else\n#else\n#endif";
+    let e = parse(source, &[]).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::UnmatchedConditional(TokenKind::Else));
+
+    let source = "#ifndef A\n#define macro MAIN() = takes (0) returns (0) {}";
+    let e = parse(source, &[]).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::UnterminatedConditional(TokenKind::IfNDef));
+    assert_eq!(e.spans.0[0].start, 0);
+
+    let source = "#ifdef 0x01\n#endif";
+    let e = parse(source, &[]).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::UnexpectedType(TokenKind::Literal(str_to_bytes32("01"))));
+}
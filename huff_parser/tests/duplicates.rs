@@ -0,0 +1,151 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Result<Contract, ParserError> {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse()
+}
+
+/// The spans of every occurrence of a name in the source
+fn occurrences(source: &str, name: &str) -> Vec<Span> {
+    source
+        .match_indices(name)
+        .map(|(start, _)| Span { start, end: start + name.len() - 1, file: None })
+        .collect()
+}
+
+#[test]
+fn test_duplicate_constant() {
+    let source = r#"
+    #define constant OWNER_SLOT = 0x00
+    #define constant OWNER_SLOT = 0x01
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::DuplicateConstant("OWNER_SLOT".to_string()));
+    assert_eq!(e.hint, Some("CONSTANT names should be unique".to_string()));
+    assert_eq!(e.spans.0, occurrences(source, "OWNER_SLOT"));
+}
+
+#[test]
+fn test_duplicate_table() {
+    let source = r#"
+    #define jumptable TABLE {
+        a b
+    }
+    #define jumptable__packed TABLE {
+        a b
+    }
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::DuplicateTable("TABLE".to_string()));
+    assert_eq!(e.spans.0, occurrences(source, "TABLE"));
+}
+
+#[test]
+fn test_duplicate_function() {
+    let source = r#"
+    #define function transfer(address,uint256) nonpayable returns (bool)
+    #define function transfer(address to, uint256 amount) nonpayable returns ()
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::DuplicateFunction("transfer".to_string()));
+    assert_eq!(e.hint, Some("FUNCTION signatures should be unique".to_string()));
+    assert_eq!(e.spans.0, occurrences(source, "transfer"));
+
+    // Overloaded functions have different signatures
+    let source = r#"
+    #define function transfer(address,uint256) nonpayable returns (bool)
+    #define function transfer(address,uint256,bytes) nonpayable returns (bool)
+    "#;
+    assert_eq!(parse(source).unwrap().functions.len(), 2);
+}
+
+#[test]
+fn test_function_selector_collision() {
+    // Both selectors are 0x42966c68
+    let source = r#"
+    #define function burn(uint) nonpayable returns ()
+    #define function collate_propagate_storage(bytes16) nonpayable returns ()
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(
+        e.kind,
+        ParserErrorKind::SelectorCollision(
+            "burn(uint256)".to_string(),
+            "collate_propagate_storage(bytes16)".to_string()
+        )
+    );
+    assert_eq!(e.hint, Some("FUNCTION and ERROR selectors should be unique".to_string()));
+    assert_eq!(
+        e.spans.0,
+        [occurrences(source, "burn"), occurrences(source, "collate_propagate_storage")].concat()
+    );
+}
+
+#[test]
+fn test_error_selector_collision() {
+    // Errors share a selector space with functions
+    let source = r#"
+    #define function burn(uint256) nonpayable returns ()
+    #define error collate_propagate_storage(bytes16 data)
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(
+        e.kind,
+        ParserErrorKind::SelectorCollision(
+            "burn(uint256)".to_string(),
+            "collate_propagate_storage(bytes16)".to_string()
+        )
+    );
+
+    let source = r#"
+    #define error collate_propagate_storage(bytes16)
+    #define error burn(uint)
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(
+        e.kind,
+        ParserErrorKind::SelectorCollision(
+            "collate_propagate_storage(bytes16)".to_string(),
+            "burn(uint256)".to_string()
+        )
+    );
+    assert_eq!(
+        e.spans.0,
+        [occurrences(source, "collate_propagate_storage"), occurrences(source, "burn")].concat()
+    );
+
+    // An error may share its name, but not its signature, with a function
+    let source = r#"
+    #define function Unauthorized() nonpayable returns ()
+    #define error Unauthorized(address)
+    "#;
+    assert!(parse(source).is_ok());
+}
+
+#[test]
+fn test_duplicate_event() {
+    let source = r#"
+    #define event Transfer(address indexed, address indexed, uint256)
+    #define event Transfer(address, address, uint256 indexed)
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::DuplicateEvent("Transfer".to_string()));
+    assert_eq!(e.spans.0, occurrences(source, "Transfer"));
+}
+
+#[test]
+fn test_duplicate_error() {
+    let source = r#"
+    #define error Unauthorized(address)
+    #define error Unauthorized(address)
+    #define error Unauthorized()
+    "#;
+    let e = parse(source).unwrap_err();
+    assert_eq!(e.kind, ParserErrorKind::DuplicateError("Unauthorized".to_string()));
+    assert_eq!(e.spans.0, occurrences(source, "Unauthorized")[..2]);
+}
@@ -1286,11 +1286,10 @@ fn test_duplicate_macro_error() {
                 ParserError {
                     kind: ParserErrorKind::DuplicateMacro("MINT".to_string()),
                     hint: Some("MACRO names should be unique".to_string()),
-                    spans: AstSpan(vec![Span {
-                        start: occurrences[1].0,
-                        end: occurrences[1].1,
-                        file: None
-                    }]),
+                    spans: AstSpan(vec![
+                        Span { start: occurrences[0].0, end: occurrences[0].1, file: None },
+                        Span { start: occurrences[1].0, end: occurrences[1].1, file: None }
+                    ]),
                     cursor: 58,
                 }
             )
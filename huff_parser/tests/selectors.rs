@@ -0,0 +1,95 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_dispatch_labels() {
+    let source = r#"
+    #define function transfer(address,uint256) nonpayable returns (bool)
+    #define function balanceOf(address) view returns (uint256)
+    #define function totalSupply() view returns (uint256)
+    #define function mint(address,uint256) payable returns ()
+
+    #define macro DISPATCH() = takes (1) returns (1) {
+        dup1 __FUNC_SIG("totalSupply()") eq supply jumpi
+    }
+
+    #define macro MAIN() = takes (0) returns (0) {
+        0x00 calldataload 0xe0 shr
+        dup1 __FUNC_SIG(transfer) eq transfer_from_main jumpi
+        dup1 0x70a08231 eq balance_of jumpi
+        DISPATCH()
+        0x00 dup1 revert
+
+        transfer_from_main:
+            stop
+        balance_of:
+            dup1 __FUNC_SIG("transfer(address to, uint amount)") eq transfer_from_balance jumpi
+            dup1 __FUNC_SIG(transfer) eq transfer_from_main jumpi
+            stop
+        transfer_from_balance:
+        supply:
+            // A selector without a jump isn't a dispatch
+            __FUNC_SIG(mint) mint_label
+            stop
+        mint_label:
+            stop
+    }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source.source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let dispatches = contract.dispatch_labels();
+    let labels = |signature: &str| {
+        let function = contract.functions.iter().find(|f| f.canonical_signature() == signature);
+        dispatches.get(&function.unwrap().signature).cloned()
+    };
+    assert_eq!(
+        labels("transfer(address,uint256)"),
+        Some(vec!["transfer_from_main".to_string(), "transfer_from_balance".to_string()])
+    );
+    assert_eq!(labels("balanceOf(address)"), Some(vec!["balance_of".to_string()]));
+    assert_eq!(labels("totalSupply()"), Some(vec!["supply".to_string()]));
+    assert_eq!(labels("mint(address,uint256)"), None);
+}
+
+#[test]
+fn test_dispatch_builtin_labels() {
+    let source = |dispatch: &str| {
+        format!(
+            r#"
+            #define function transfer(address,uint256) nonpayable returns (bool)
+            #define function balanceOf(address) view returns (uint256)
+            #define jumptable LABELS {{
+                give query
+            }}
+            #define jumptable SHORT {{
+                give
+            }}
+
+            #define macro MAIN() = takes (0) returns (0) {{
+                {dispatch}
+            }}
+            "#
+        )
+    };
+    let dispatch_labels = |dispatch: &str| {
+        let source = source(dispatch);
+        let lexer = Lexer::new(&source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let contract = parser.parse().unwrap();
+        contract.dispatch_labels().into_values().collect::<Vec<_>>()
+    };
+
+    // Selectors are ordered, so balanceOf comes first
+    let labels = |l: [&str; 2]| l.map(|l| vec![l.to_string()]).to_vec();
+    assert_eq!(dispatch_labels("__DISPATCH(binary)"), labels(["balanceOf", "transfer"]));
+    assert_eq!(dispatch_labels("__DISPATCH(linear, LABELS)"), labels(["query", "give"]));
+
+    // A table without a label for each function dispatches nothing
+    assert!(dispatch_labels("__DISPATCH(linear, SHORT)").is_empty());
+}
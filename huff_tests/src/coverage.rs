@@ -0,0 +1,227 @@
+use crate::prelude::{RunnerError, TestResult};
+use huff_utils::prelude::{AstSpan, Contract, SourceMap, Statement, StatementType};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// The default LCOV output file name
+pub const LCOV_FILE: &str = "lcov.info";
+
+/// The execution count of a single statement
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageItem {
+    /// The bytecode offset of the statement
+    pub pc: usize,
+    /// The macro the statement is defined in
+    pub macro_name: String,
+    /// The label block the statement is defined in, if any
+    pub label: Option<String>,
+    /// The path of the file the statement is defined in
+    pub file: Option<String>,
+    /// The offset of the statement within its file
+    pub offset: usize,
+    /// The line of the statement within its file
+    pub line: Option<usize>,
+    /// The number of times the statement was executed
+    pub hits: usize,
+    /// Whether the statement is defined in a test macro
+    pub test: bool,
+}
+
+impl CoverageItem {
+    fn new(macro_name: &str, label: Option<&String>, span: &AstSpan, hits: usize) -> Self {
+        let span = span.0.first();
+        CoverageItem {
+            pc: 0,
+            macro_name: macro_name.to_string(),
+            label: label.cloned(),
+            file: span.and_then(|s| s.file.as_ref()).map(|f| f.path.clone()),
+            offset: span.map(|s| s.start).unwrap_or_default(),
+            line: span.and_then(|s| s.line()),
+            hits,
+            test: false,
+        }
+    }
+}
+
+/// Map the executed pcs of a test back to the statements that generated them.
+///
+/// Each source map entry covers the bytecode up until the next entry with a greater offset,
+/// and is credited with the most executed pc within that range.
+pub fn map_coverage(executed: &[CoverageItem], source_map: &SourceMap) -> Vec<CoverageItem> {
+    let executed = executed.iter().map(|i| (i.pc, i.hits)).collect::<BTreeMap<usize, usize>>();
+    source_map
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let end = source_map[i + 1..]
+                .iter()
+                .find(|e| e.pc > entry.pc)
+                .map(|e| e.pc)
+                .unwrap_or(usize::MAX);
+            let hits = executed.range(entry.pc..end).map(|(_, h)| *h).max().unwrap_or_default();
+            CoverageItem {
+                pc: entry.pc,
+                test: entry.test,
+                ..CoverageItem::new(&entry.macro_name, entry.label.as_ref(), &entry.span, hits)
+            }
+        })
+        .collect()
+}
+
+/// Generate unexecuted coverage items for every statement of the contract's non-test macros,
+/// so that macros which are never invoked by a test are still reported.
+pub fn seed_coverage(contract: &Contract) -> Vec<CoverageItem> {
+    fn statement_items(
+        macro_name: &str,
+        label: Option<&String>,
+        statements: &[Statement],
+        items: &mut Vec<CoverageItem>,
+    ) {
+        let mut statement_iter = statements.iter();
+        while let Some(statement) = statement_iter.next() {
+            match &statement.ty {
+                StatementType::Label(l) => {
+                    items.push(CoverageItem::new(macro_name, Some(&l.name), &statement.span, 0));
+                    statement_items(macro_name, Some(&l.name), &l.inner, items);
+                }
+                ty => {
+                    items.push(CoverageItem::new(macro_name, label, &statement.span, 0));
+                    // The literal of a push opcode shares the opcode's bytecode
+                    if matches!(ty, StatementType::Opcode(o) if o.is_value_push()) {
+                        statement_iter.next();
+                    }
+                }
+            }
+        }
+    }
+
+    let mut items = vec![];
+    contract
+        .macros
+        .iter()
+        .filter(|m| !m.test)
+        .for_each(|m| statement_items(&m.name, None, &m.statements, &mut items));
+    items
+}
+
+/// The coverage of a macro or label block
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockCoverage {
+    /// The first line of the block
+    pub line: usize,
+    /// The number of times the block was entered
+    pub hits: usize,
+    /// The number of statements in the block
+    pub statements: usize,
+    /// The number of executed statements in the block
+    pub covered: usize,
+}
+
+impl BlockCoverage {
+    fn add(&mut self, item: &CoverageItem, line: usize) {
+        self.line = if self.statements == 0 { line } else { self.line.min(line) };
+        self.hits = self.hits.max(item.hits);
+        self.statements += 1;
+        self.covered += (item.hits > 0) as usize;
+    }
+}
+
+/// The coverage of a single file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// Line numbers mapped to their execution counts
+    pub lines: BTreeMap<usize, usize>,
+    /// Macro names mapped to their coverage
+    pub macros: BTreeMap<String, BlockCoverage>,
+    /// Macro and label names mapped to the label block's coverage
+    pub labels: BTreeMap<(String, String), BlockCoverage>,
+}
+
+/// Coverage aggregated over all tests, keyed by file path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub files: BTreeMap<String, FileCoverage>,
+}
+
+impl CoverageReport {
+    /// Aggregate the coverage of test results, excluding the test macros themselves.
+    pub fn new(contracts: &[Contract], results: &[TestResult]) -> Self {
+        // Sum the executions of each statement across all tests and inlined invocations
+        let mut statements: BTreeMap<(String, usize), CoverageItem> = BTreeMap::new();
+        contracts
+            .iter()
+            .flat_map(seed_coverage)
+            .chain(results.iter().flat_map(|r| r.coverage.iter().flatten().cloned()))
+            .filter(|i| !i.test)
+            .for_each(|item| {
+                if let Some(file) = &item.file {
+                    statements
+                        .entry((file.clone(), item.offset))
+                        .and_modify(|i| i.hits += item.hits)
+                        .or_insert(item);
+                }
+            });
+
+        let mut report = CoverageReport::default();
+        for ((file, _), item) in statements {
+            let line = match item.line {
+                Some(line) => line,
+                None => continue,
+            };
+            let file_coverage = report.files.entry(file).or_default();
+            let line_hits = file_coverage.lines.entry(line).or_default();
+            *line_hits = (*line_hits).max(item.hits);
+            file_coverage.macros.entry(item.macro_name.clone()).or_default().add(&item, line);
+            if let Some(label) = &item.label {
+                file_coverage
+                    .labels
+                    .entry((item.macro_name.clone(), label.clone()))
+                    .or_default()
+                    .add(&item, line);
+            }
+        }
+        report
+    }
+
+    /// Format the report as an LCOV tracefile.
+    ///
+    /// Macros are reported as functions and label blocks as branches of their macro.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        for (file, coverage) in &self.files {
+            lcov.push_str(&format!("TN:\nSF:{file}\n"));
+            for (name, m) in &coverage.macros {
+                lcov.push_str(&format!("FN:{},{name}\n", m.line));
+            }
+            for (name, m) in &coverage.macros {
+                lcov.push_str(&format!("FNDA:{},{name}\n", m.hits));
+            }
+            lcov.push_str(&format!("FNF:{}\n", coverage.macros.len()));
+            lcov.push_str(&format!(
+                "FNH:{}\n",
+                coverage.macros.values().filter(|m| m.hits > 0).count()
+            ));
+            for (i, l) in coverage.labels.values().enumerate() {
+                lcov.push_str(&format!("BRDA:{},0,{i},{}\n", l.line, l.hits));
+            }
+            lcov.push_str(&format!("BRF:{}\n", coverage.labels.len()));
+            lcov.push_str(&format!(
+                "BRH:{}\n",
+                coverage.labels.values().filter(|l| l.hits > 0).count()
+            ));
+            for (line, hits) in &coverage.lines {
+                lcov.push_str(&format!("DA:{line},{hits}\n"));
+            }
+            lcov.push_str(&format!("LF:{}\n", coverage.lines.len()));
+            lcov.push_str(&format!("LH:{}\n", coverage.lines.values().filter(|h| **h > 0).count()));
+            lcov.push_str("end_of_record\n");
+        }
+        lcov
+    }
+}
+
+/// Write a coverage report as an LCOV tracefile to the given path
+pub fn write_lcov(report: &CoverageReport, path: impl AsRef<Path>) -> Result<(), RunnerError> {
+    fs::write(path.as_ref(), report.to_lcov()).map_err(|e| {
+        RunnerError(format!("Failed to write LCOV file to \"{}\": {e}", path.as_ref().display()))
+    })
+}
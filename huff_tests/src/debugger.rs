@@ -0,0 +1,294 @@
+use crate::prelude::{TestResult, TestStatus, TraceStep};
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+use yansi::Paint;
+
+/// The commands of the step debugger, as printed by `help`
+pub const DEBUG_HELP: &str = "Commands:
+  s, step           Execute a single opcode
+  n, next           Execute until the next source line, stepping over calls
+  c, continue       Execute until a breakpoint is hit, or the execution ends
+  b, break <label>  Set or remove a breakpoint on a label
+  h, help           Print this message
+  q, quit           Exit the debugger
+An empty line repeats the last command.";
+
+/// A command of the step debugger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugCommand {
+    /// Execute a single opcode
+    Step,
+    /// Execute until the next source line, stepping over calls to other contracts
+    Next,
+    /// Execute until a breakpoint is hit, or the execution ends
+    Continue,
+    /// Set or remove a breakpoint on a label
+    Break(String),
+    /// Print the available commands
+    Help,
+    /// Exit the debugger
+    Quit,
+}
+
+impl FromStr for DebugCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["s" | "step"] => Ok(DebugCommand::Step),
+            ["n" | "next"] => Ok(DebugCommand::Next),
+            ["c" | "continue"] => Ok(DebugCommand::Continue),
+            ["b" | "break", label] => Ok(DebugCommand::Break(label.to_string())),
+            ["h" | "help"] => Ok(DebugCommand::Help),
+            ["q" | "quit"] => Ok(DebugCommand::Quit),
+            _ => Err(format!(
+                "Unknown command \"{}\". Type \"help\" for a list of commands.",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// An interactive step debugger over the recorded execution of a test.
+///
+/// The test is run to completion before debugging, with the full stack and memory recorded at
+/// every step, so that the debugger only moves through the recorded steps.
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    /// The result of the debugged test
+    pub result: TestResult,
+    /// The recorded steps of the test's execution
+    pub trace: Vec<TraceStep>,
+    /// The index of the current step, which is about to be executed
+    pub position: usize,
+    /// The labels that `continue` stops at
+    pub breakpoints: BTreeSet<String>,
+}
+
+impl Debugger {
+    /// Create a debugger from the result of a test run with debugging enabled.
+    pub fn new(mut result: TestResult) -> Self {
+        let trace = result.trace.take().unwrap_or_default();
+        Self { result, trace, position: 0, breakpoints: BTreeSet::default() }
+    }
+
+    /// The current step, if any steps were executed
+    pub fn current(&self) -> Option<&TraceStep> {
+        self.trace.get(self.position)
+    }
+
+    /// Apply a command, moving through the recorded steps. Returns a message to display
+    /// alongside the current step, if any.
+    pub fn apply(&mut self, command: &DebugCommand) -> Option<String> {
+        let current = self.current()?.clone();
+        let target = match command {
+            DebugCommand::Step => self.find(|_| true),
+            // Huff macros are inlined, so the next source line may belong to a nested macro
+            DebugCommand::Next => self.find(|step| {
+                step.depth <= current.depth &&
+                    (current.location.is_none() || step.location != current.location)
+            }),
+            DebugCommand::Continue => {
+                let target = self.find(|step| {
+                    step.opcode == "JUMPDEST" &&
+                        step.label.as_ref().is_some_and(|l| self.breakpoints.contains(l))
+                });
+                if let Some(target) = target {
+                    self.position = target;
+                    let label = self.trace[target].label.clone().unwrap_or_default();
+                    return Some(format!("Hit breakpoint on label \"{label}\""))
+                }
+                None
+            }
+            DebugCommand::Break(label) => {
+                if self.breakpoints.remove(label) {
+                    return Some(format!("Removed breakpoint on label \"{label}\""))
+                }
+                if !self.trace.iter().any(|step| step.label.as_ref() == Some(label)) {
+                    return Some(format!("Label \"{label}\" is never reached by the test"))
+                }
+                self.breakpoints.insert(label.clone());
+                return Some(format!("Set breakpoint on label \"{label}\""))
+            }
+            DebugCommand::Help => return Some(DEBUG_HELP.to_string()),
+            DebugCommand::Quit => return None,
+        };
+
+        match target {
+            Some(target) => {
+                self.position = target;
+                None
+            }
+            None => {
+                self.position = self.trace.len() - 1;
+                Some(self.outcome())
+            }
+        }
+    }
+
+    /// Find the first step after the current step that matches a predicate
+    fn find(&self, predicate: impl Fn(&TraceStep) -> bool) -> Option<usize> {
+        self.trace
+            .iter()
+            .enumerate()
+            .skip(self.position + 1)
+            .find(|(_, s)| predicate(s))
+            .map(|(i, _)| i)
+    }
+
+    /// Describe how the test's execution ended
+    pub fn outcome(&self) -> String {
+        match self.result.status {
+            TestStatus::Success => String::from("Execution finished: the test passed"),
+            TestStatus::Revert => match (&self.result.revert_reason, &self.result.return_data) {
+                (Some(reason), _) => format!("Execution finished: the test reverted with {reason}"),
+                (None, Some(data)) => {
+                    format!("Execution finished: the test reverted with data: 0x{data}")
+                }
+                (None, None) => String::from("Execution finished: the test reverted"),
+            },
+        }
+    }
+
+    /// Render the state of the current step: its source line, opcode, stack, and the memory and
+    /// storage changes made so far.
+    pub fn render(&self) -> String {
+        let Some(step) = self.current() else {
+            return format!("{}\nNo steps were executed\n", Paint::magenta(&self.result.name))
+        };
+        let mut out = format!(
+            "{} {}\n",
+            Paint::magenta(&self.result.name),
+            Paint::yellow(format!("step {}/{}", self.position + 1, self.trace.len()))
+        );
+
+        // The source line, with the macro and label it belongs to
+        out.push_str(&format!("{}\n", Paint::cyan("SOURCE")));
+        let mut context = step.macro_name.clone().unwrap_or_else(|| String::from("(external)"));
+        if let Some(label) = &step.label {
+            context = format!("{context} ➜ {label}");
+        }
+        out.push_str(&format!(
+            "├─ {} {}\n",
+            Paint::magenta(context),
+            Paint::blue(step.location.as_deref().unwrap_or_default())
+        ));
+        out.push_str(&format!("╰─ {}\n", step.source.as_deref().unwrap_or_default().trim()));
+
+        // The opcode about to be executed
+        out.push_str(&format!(
+            "{}\n╰─ [{}: {}] {} {}\n",
+            Paint::cyan("OPCODE"),
+            Paint::magenta("PC"),
+            Paint::yellow(format!("{:#06x}", step.pc)),
+            step.opcode,
+            Paint::blue(format!("(depth {})", step.depth))
+        ));
+
+        // The stack, alongside the stack comment of the source line
+        let comment = step.source.as_deref().and_then(|s| s.find("//").map(|i| s[i..].trim()));
+        match comment {
+            Some(comment) => out.push_str(&format!("{} {comment}\n", Paint::cyan("STACK"))),
+            None => out.push_str(&format!("{}\n", Paint::cyan("STACK"))),
+        }
+        let stack = step.stack.clone().unwrap_or_default();
+        if stack.is_empty() {
+            out.push_str("╰─ []\n");
+        }
+        for (i, value) in stack.iter().enumerate() {
+            let branch = if i == stack.len() - 1 { "╰─" } else { "├─" };
+            out.push_str(&format!("{branch} {}: {value}\n", Paint::yellow(i)));
+        }
+
+        // The memory, with the words changed by the previous step highlighted
+        out.push_str(&format!("{}\n", Paint::cyan("MEMORY")));
+        let memory = step.memory.as_deref().unwrap_or_default();
+        let previous = self
+            .position
+            .checked_sub(1)
+            .and_then(|i| self.trace[i].memory.as_deref())
+            .unwrap_or_default();
+        let words = memory.len().div_ceil(64);
+        if words == 0 {
+            out.push_str("╰─ []\n");
+        }
+        for i in 0..words {
+            let word = &memory[i * 64..memory.len().min((i + 1) * 64)];
+            let word = if previous.get(i * 64..(i + 1) * 64) == Some(word) {
+                Paint::new(word)
+            } else {
+                Paint::green(word)
+            };
+            let branch = if i == words - 1 { "╰─" } else { "├─" };
+            out.push_str(&format!(
+                "{branch} {}: {word}\n",
+                Paint::yellow(format!("{:#06x}", i * 32))
+            ));
+        }
+
+        // The storage slots written by the test so far, with the latest write highlighted
+        out.push_str(&format!("{}\n", Paint::cyan("STORAGE")));
+        let depth = self.trace[0].depth;
+        let mut writes: Vec<(&String, &String)> = Vec::new();
+        for s in self.trace[..self.position].iter().filter(|s| s.depth == depth) {
+            if let Some((slot, value)) = &s.storage_write {
+                match writes.iter_mut().find(|(written, _)| *written == slot) {
+                    Some(write) => write.1 = value,
+                    None => writes.push((slot, value)),
+                }
+            }
+        }
+        let latest = self
+            .position
+            .checked_sub(1)
+            .and_then(|i| self.trace[i].storage_write.as_ref())
+            .map(|(slot, _)| slot);
+        if writes.is_empty() {
+            out.push_str("╰─ []\n");
+        }
+        for (i, (slot, value)) in writes.iter().enumerate() {
+            let write = format!("{slot}: {value}");
+            let write = if latest == Some(slot) { Paint::green(write) } else { Paint::new(write) };
+            out.push_str(&format!("{} {write}\n", if i == writes.len() - 1 { "╰─" } else { "├─" }));
+        }
+        out
+    }
+
+    /// Run the debugger interactively, reading commands from `input` until the user quits or
+    /// the input ends. The terminal is redrawn after every command.
+    pub fn run(&mut self, input: &mut impl BufRead) -> io::Result<()> {
+        let mut message = Some(String::from("Type \"help\" for a list of commands."));
+        let mut last = DebugCommand::Step;
+        loop {
+            print!("\x1b[2J\x1b[H{}", self.render());
+            if let Some(message) = message.take() {
+                println!("{message}");
+            }
+            print!("{} ", Paint::blue("(debug)"));
+            io::stdout().flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(())
+            }
+            let command = match line.trim() {
+                "" => last.clone(),
+                line => match line.parse::<DebugCommand>() {
+                    Ok(command) => command,
+                    Err(e) => {
+                        message = Some(Paint::red(e).to_string());
+                        continue
+                    }
+                },
+            };
+            if command == DebugCommand::Quit {
+                return Ok(())
+            }
+            message = self.apply(&command);
+            last = command;
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use ethers_core::{
+    abi::{decode, param_type::Reader, ParamType, Token},
+    types::I256,
+    utils::hex,
+};
+use huff_utils::prelude::ErrorDefinition;
+
+/// The selector of `Error(string)`
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// The selector of `Panic(uint256)`
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decode revert data into a human readable reason.
+///
+/// `Error(string)` and `Panic(uint256)` are always recognized, as well as any custom error
+/// whose selector matches one of the given error definitions. Returns `None` if the data
+/// matches none of them, or cannot be decoded.
+pub fn decode_revert(data: &[u8], errors: &[ErrorDefinition]) -> Option<String> {
+    let (selector, args) = (data.get(..4)?, &data[4..]);
+    if selector == ERROR_SELECTOR {
+        let tokens = decode(&[ParamType::String], args).ok()?;
+        return Some(format!("Error({})", format_token(&tokens[0])))
+    }
+    if selector == PANIC_SELECTOR {
+        let code = decode(&[ParamType::Uint(256)], args).ok()?[0].clone().into_uint()?;
+        let reason = match code.low_u64() {
+            _ if code.bits() > 8 => None,
+            0x00 => Some("generic panic"),
+            0x01 => Some("assertion failed"),
+            0x11 => Some("arithmetic underflow or overflow"),
+            0x12 => Some("division or modulo by zero"),
+            0x21 => Some("invalid enum value"),
+            0x22 => Some("invalid storage byte array encoding"),
+            0x31 => Some("pop on an empty array"),
+            0x32 => Some("out-of-bounds array access"),
+            0x41 => Some("out of memory"),
+            0x51 => Some("call to an uninitialized function"),
+            _ => None,
+        };
+        return Some(match reason {
+            Some(reason) => format!("Panic(0x{:02x}: {reason})", code.low_u64()),
+            None => format!("Panic({code})"),
+        })
+    }
+
+    let error = errors.iter().find(|e| e.selector == selector)?;
+    let kinds = error
+        .parameters
+        .iter()
+        .map(|p| Reader::read(p.arg_type.as_deref().unwrap_or_default()).ok())
+        .collect::<Option<Vec<ParamType>>>()?;
+    let tokens = decode(&kinds, args).ok()?;
+    let args = error
+        .parameters
+        .iter()
+        .zip(tokens.iter())
+        .map(|(p, t)| {
+            let label = p.name.as_deref().or(p.arg_type.as_deref()).unwrap_or_default();
+            format!("{label}={}", format_token(t))
+        })
+        .collect::<Vec<String>>();
+    Some(format!("{}({})", error.name, args.join(", ")))
+}
+
+/// Format a decoded ABI token the way it would be written in source code
+pub fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(a) => format!("0x{}", hex::encode(a)),
+        Token::Bytes(b) | Token::FixedBytes(b) => format!("0x{}", hex::encode(b)),
+        Token::Uint(u) => u.to_string(),
+        Token::Int(i) => I256::from_raw(*i).to_string(),
+        Token::Bool(b) => b.to_string(),
+        Token::String(s) => format!("{s:?}"),
+        Token::Array(tokens) | Token::FixedArray(tokens) => {
+            format!("[{}]", tokens.iter().map(format_token).collect::<Vec<String>>().join(", "))
+        }
+        Token::Tuple(tokens) => {
+            format!("({})", tokens.iter().map(format_token).collect::<Vec<String>>().join(", "))
+        }
+    }
+}
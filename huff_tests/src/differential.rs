@@ -0,0 +1,306 @@
+use crate::prelude::{
+    abi_functions, random_call, test_path, trace_inspector::format_word, FuzzCall, FuzzConfig,
+    RunnerError, TestResult, TestRunner, TestStatus,
+};
+use ethers_core::{abi::Function, types::Address, utils::hex};
+use huff_utils::prelude::Contract;
+use rand::{rngs::StdRng, SeedableRng};
+use revm::{
+    primitives::{ExecutionResult, B160, B256, U256 as RU256},
+    InMemoryDB,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+/// The observable effects of a call, compared between the Huff and reference implementations
+#[derive(Debug, Clone, Default)]
+pub struct CallOutcome {
+    /// Whether the call succeeded
+    pub success: bool,
+    /// The return data, or revert data of a reverted call
+    pub output: Vec<u8>,
+    /// The topics and data of the logs emitted by the call
+    pub logs: Vec<(Vec<B256>, Vec<u8>)>,
+    /// The non-zero storage slots of the called contract after the call
+    pub storage: BTreeMap<RU256, RU256>,
+    /// The gas used by the call, without the base cost of the transaction
+    pub gas: u64,
+}
+
+impl CallOutcome {
+    /// Describe each difference between the outcome of the Huff implementation and the outcome
+    /// of the reference implementation. Gas usage is not compared.
+    pub fn diff(&self, reference: &CallOutcome) -> Vec<String> {
+        let status = |success| if success { "success" } else { "revert" };
+        let mut diffs = Vec::new();
+        if self.success != reference.success {
+            diffs.push(format!(
+                "Status differs: {} (Huff) != {} (reference)",
+                status(self.success),
+                status(reference.success)
+            ));
+        }
+        if self.output != reference.output {
+            diffs.push(format!(
+                "Return data differs: 0x{} (Huff) != 0x{} (reference)",
+                hex::encode(&self.output),
+                hex::encode(&reference.output)
+            ));
+        }
+        if self.logs != reference.logs {
+            diffs.push(format!(
+                "Logs differ: {} (Huff) != {} (reference)",
+                format_logs(&self.logs),
+                format_logs(&reference.logs)
+            ));
+        }
+        let slots = self.storage.keys().chain(reference.storage.keys()).collect::<BTreeSet<_>>();
+        for slot in slots {
+            let (huff, reference) = (self.storage.get(slot), reference.storage.get(slot));
+            if huff != reference {
+                diffs.push(format!(
+                    "Storage slot {} differs: {} (Huff) != {} (reference)",
+                    format_word(slot),
+                    format_word(&huff.copied().unwrap_or_default()),
+                    format_word(&reference.copied().unwrap_or_default())
+                ));
+            }
+        }
+        diffs
+    }
+}
+
+/// Format emitted logs as a list of their topics and data
+fn format_logs(logs: &[(Vec<B256>, Vec<u8>)]) -> String {
+    let logs = logs
+        .iter()
+        .map(|(topics, data)| {
+            let topics =
+                topics.iter().map(|t| format!("0x{}", hex::encode(t))).collect::<Vec<String>>();
+            format!("[{}] 0x{}", topics.join(", "), hex::encode(data))
+        })
+        .collect::<Vec<String>>();
+    format!("[{}]", logs.join(", "))
+}
+
+/// Read the creation bytecode of a reference implementation from an artifact JSON, as written
+/// by solc, Foundry, Hardhat or huffc, or from a file containing the hex encoded bytecode.
+pub fn read_reference_bytecode(path: impl AsRef<Path>) -> Result<Vec<u8>, RunnerError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path).map_err(|e| {
+        RunnerError(format!("Failed to read reference artifact \"{}\": {e}", path.display()))
+    })?;
+    let code = match serde_json::from_str::<serde_json::Value>(&contents) {
+        Ok(artifact) if artifact.is_object() => [
+            &artifact["bytecode"],
+            &artifact["bytecode"]["object"],
+            &artifact["evm"]["bytecode"]["object"],
+        ]
+        .into_iter()
+        .find_map(|v| v.as_str())
+        .ok_or_else(|| {
+            RunnerError(format!("No bytecode found in reference artifact \"{}\"", path.display()))
+        })?
+        .to_string(),
+        _ => contents.trim().to_string(),
+    };
+    hex::decode(code.trim_start_matches("0x")).map_err(|e| {
+        RunnerError(format!("Invalid reference bytecode in \"{}\": {e}", path.display()))
+    })
+}
+
+impl TestRunner {
+    /// Run a differential testing campaign.
+    ///
+    /// The contract and a reference implementation are deployed side by side, and random
+    /// sequences of calls to the contract's functions are made to both with identical
+    /// calldata. After each call, their revert status, return data, emitted logs and storage
+    /// are compared. Each function gets a result holding the most gas any of its calls used,
+    /// and functions whose outcomes diverged are reported with the minimized sequence of calls
+    /// that led to the divergence.
+    pub fn run_differential(
+        &mut self,
+        contract: &Contract,
+        reference: &[u8],
+        config: &FuzzConfig,
+    ) -> Result<Vec<TestResult>, RunnerError> {
+        let functions = abi_functions(contract.functions.iter())?;
+        let target = self.deploy_main(contract)?;
+        let reference = self
+            .create(reference.to_vec().into())
+            .map_err(|e| RunnerError(format!("Reference deployment failed: {}", e.0)))?;
+        let initial = self.database.clone();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+
+        // Functions are reported as defined in the file of the contract's `MAIN` macro
+        let path = contract.find_macro_by_name("MAIN").and_then(test_path);
+        let mut results = contract
+            .functions
+            .iter()
+            .map(|f| TestResult {
+                name: TestRunner::function_signature(f),
+                path: path.clone(),
+                return_data: None,
+                revert_reason: None,
+                counterexample: None,
+                divergence: None,
+                gas: 0,
+                status: TestStatus::Success,
+                logs: Vec::default(),
+                trace: None,
+                coverage: None,
+            })
+            .collect::<Vec<TestResult>>();
+        if functions.is_empty() {
+            return Ok(results)
+        }
+
+        for _ in 0..config.runs {
+            self.database = initial.clone();
+            let mut sequence = Vec::new();
+            for _ in 0..config.depth {
+                let call = random_call(&mut rng, &functions);
+                let index = function_index(&functions, &call);
+                let (huff, expected) = self.compare_call(&call, target, reference)?;
+                sequence.push(call);
+
+                // Functions report the most gas any of their calls used during the campaign
+                results[index].gas = results[index].gas.max(huff.gas);
+                if huff.diff(&expected).is_empty() {
+                    continue
+                }
+
+                if let TestStatus::Success = results[index].status {
+                    let (calls, diffs) =
+                        self.shrink_divergence(&functions, target, reference, &initial, sequence)?;
+                    let result = &mut results[index];
+                    result.status = TestStatus::Revert;
+                    result.counterexample = Some(calls.iter().map(ToString::to_string).collect());
+                    result.divergence = Some(diffs);
+                }
+
+                // Once the implementations diverged, their states may no longer be comparable
+                break
+            }
+        }
+        Ok(results)
+    }
+
+    /// Make the same call to the Huff and reference implementations, returning both outcomes.
+    fn compare_call(
+        &mut self,
+        call: &FuzzCall,
+        target: Address,
+        reference: Address,
+    ) -> Result<(CallOutcome, CallOutcome), RunnerError> {
+        let data =
+            call.function.encode_input(&call.args).map_err(|e| RunnerError(e.to_string()))?;
+        Ok((
+            self.call_outcome(call.caller, target, data.clone())?,
+            self.call_outcome(call.caller, reference, data)?,
+        ))
+    }
+
+    /// Call a deployed contract and collect the observable effects of the call.
+    fn call_outcome(
+        &mut self,
+        caller: Address,
+        address: Address,
+        data: Vec<u8>,
+    ) -> Result<CallOutcome, RunnerError> {
+        let (success, output, logs, gas_used) = match self.transact(caller, address, data.into())? {
+            ExecutionResult::Success { output, logs, gas_used, .. } => (
+                true,
+                output.into_data().to_vec(),
+                logs.into_iter().map(|l| (l.topics, l.data.to_vec())).collect(),
+                gas_used,
+            ),
+            ExecutionResult::Revert { output, gas_used } => {
+                (false, output.to_vec(), Vec::default(), gas_used)
+            }
+            ExecutionResult::Halt { gas_used, .. } => {
+                (false, Vec::default(), Vec::default(), gas_used)
+            }
+        };
+        let storage = self
+            .database
+            .accounts
+            .get(&B160::from_slice(address.as_bytes()))
+            .map(|account| {
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| **value != RU256::ZERO)
+                    .map(|(slot, value)| (*slot, *value))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(CallOutcome { success, output, logs, storage, gas: gas_used.saturating_sub(21000) })
+    }
+
+    /// Replay a sequence of calls against the initial state of both implementations. Returns
+    /// the number of calls after which their outcomes first diverged, and the differences.
+    fn replay_divergence(
+        &mut self,
+        target: Address,
+        reference: Address,
+        initial: &InMemoryDB,
+        sequence: &[FuzzCall],
+    ) -> Result<Option<(usize, Vec<String>)>, RunnerError> {
+        self.database = initial.clone();
+        for (i, call) in sequence.iter().enumerate() {
+            let (huff, expected) = self.compare_call(call, target, reference)?;
+            let diffs = huff.diff(&expected);
+            if !diffs.is_empty() {
+                return Ok(Some((i + 1, diffs)))
+            }
+        }
+        Ok(None)
+    }
+
+    /// Minimize a sequence of calls after which the implementations diverged, by removing
+    /// every call that is not needed for a call to the same function to diverge.
+    fn shrink_divergence(
+        &mut self,
+        functions: &[Function],
+        target: Address,
+        reference: Address,
+        initial: &InMemoryDB,
+        mut sequence: Vec<FuzzCall>,
+    ) -> Result<(Vec<FuzzCall>, Vec<String>), RunnerError> {
+        let index = sequence.last().map(|call| function_index(functions, call));
+        let (len, mut diffs) = self
+            .replay_divergence(target, reference, initial, &sequence)?
+            .ok_or_else(|| RunnerError(String::from("Failed to replay the diverging calls")))?;
+        sequence.truncate(len);
+
+        let mut shrunk = true;
+        while shrunk {
+            shrunk = false;
+            for i in (0..sequence.len()).rev() {
+                let mut candidate = sequence.clone();
+                candidate.remove(i);
+                if let Some((len, candidate_diffs)) =
+                    self.replay_divergence(target, reference, initial, &candidate)?
+                {
+                    candidate.truncate(len);
+                    if candidate.last().map(|call| function_index(functions, call)) == index {
+                        (sequence, diffs) = (candidate, candidate_diffs);
+                        shrunk = true;
+                        break
+                    }
+                }
+            }
+        }
+        Ok((sequence, diffs))
+    }
+}
+
+/// The index of the called function among the campaign's functions
+fn function_index(functions: &[Function], call: &FuzzCall) -> usize {
+    let signature = call.function.signature();
+    functions.iter().position(|f| f.signature() == signature).unwrap_or_default()
+}
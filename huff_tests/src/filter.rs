@@ -0,0 +1,79 @@
+use crate::prelude::RunnerError;
+use glob::Pattern;
+use huff_utils::prelude::MacroDefinition;
+use regex::Regex;
+
+/// A pattern that matches test names or file paths, either as a glob or as a regex.
+///
+/// Regexes must match the whole input, so a plain test name only matches that exact test.
+#[derive(Debug, Clone)]
+pub struct TestPattern {
+    /// The pattern interpreted as a glob, if it is a valid glob
+    pub glob: Option<Pattern>,
+    /// The pattern interpreted as a regex, if it is a valid regex
+    pub regex: Option<Regex>,
+}
+
+impl TestPattern {
+    /// Create a pattern, failing if it is neither a valid glob nor a valid regex
+    pub fn new(pattern: &str) -> Result<Self, RunnerError> {
+        let glob = Pattern::new(pattern).ok();
+        let regex = Regex::new(&format!("^(?:{pattern})$")).ok();
+        if glob.is_none() && regex.is_none() {
+            return Err(RunnerError(format!("Invalid test pattern: \"{pattern}\"")))
+        }
+        Ok(Self { glob, regex })
+    }
+
+    /// Whether the input matches the pattern as either a glob or a regex
+    pub fn is_match(&self, input: &str) -> bool {
+        self.glob.as_ref().is_some_and(|g| g.matches(input)) ||
+            self.regex.as_ref().is_some_and(|r| r.is_match(input))
+    }
+}
+
+/// Filters that select which of the discovered tests are run
+#[derive(Debug, Clone, Default)]
+pub struct TestFilter {
+    /// Only select tests whose name matches the pattern
+    pub match_: Option<TestPattern>,
+    /// Only select tests defined in files whose path matches the pattern
+    pub match_path: Option<TestPattern>,
+    /// Skip tests whose name or file path matches the pattern
+    pub skip: Option<TestPattern>,
+}
+
+impl TestFilter {
+    /// Create a filter from the optional `match`, `match-path` and `skip` patterns
+    pub fn new(
+        match_: Option<&str>,
+        match_path: Option<&str>,
+        skip: Option<&str>,
+    ) -> Result<Self, RunnerError> {
+        Ok(Self {
+            match_: match_.map(TestPattern::new).transpose()?,
+            match_path: match_path.map(TestPattern::new).transpose()?,
+            skip: skip.map(TestPattern::new).transpose()?,
+        })
+    }
+
+    /// Whether a test macro is selected by the filter
+    pub fn is_match(&self, m: &MacroDefinition) -> bool {
+        let path = test_path(m);
+        // Paths are matched both as they were given to the compiler and without a leading `./`
+        let path_matches = |p: &TestPattern| {
+            path.as_ref().is_some_and(|path| {
+                p.is_match(path) || path.strip_prefix("./").is_some_and(|path| p.is_match(path))
+            })
+        };
+
+        self.match_.as_ref().is_none_or(|p| p.is_match(&m.name)) &&
+            self.match_path.as_ref().is_none_or(path_matches) &&
+            !self.skip.as_ref().is_some_and(|p| p.is_match(&m.name) || path_matches(p))
+    }
+}
+
+/// The path of the file a test macro is defined in, if it is known
+pub fn test_path(m: &MacroDefinition) -> Option<String> {
+    m.span.0.first().and_then(|s| s.file.as_ref()).map(|f| f.path.clone())
+}
@@ -0,0 +1,134 @@
+use crate::prelude::{format_token, RunnerError, TestRunner};
+use ethers_core::{
+    abi::{AbiParser, Function, ParamType, Token},
+    types::{Address, U256},
+    utils::hex,
+};
+use huff_utils::ast::FunctionDefinition;
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng};
+use std::fmt;
+
+/// The accounts that the random calls of a campaign are sent from
+pub const FUZZ_CALLERS: [u64; 3] = [0x10000, 0x20000, 0x30000];
+
+/// The configuration of a campaign of random calls, run by invariant and differential tests
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzConfig {
+    /// The number of call sequences to run
+    pub runs: usize,
+    /// The number of calls in each sequence
+    pub depth: usize,
+    /// The seed of the random number generator, so that campaigns can be reproduced
+    pub seed: u64,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self { runs: 256, depth: 15, seed: 0 }
+    }
+}
+
+/// A random call to the contract under test, made during a campaign
+#[derive(Debug, Clone)]
+pub struct FuzzCall {
+    /// The account the call is sent from
+    pub caller: Address,
+    /// The called function
+    pub function: Function,
+    /// The arguments of the call
+    pub args: Vec<Token>,
+}
+
+/// fmt::Display implementation for `FuzzCall`
+impl fmt::Display for FuzzCall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let args = self.args.iter().map(format_token).collect::<Vec<String>>();
+        write!(f, "{}({}) from 0x{}", self.function.name, args.join(", "), hex::encode(self.caller))
+    }
+}
+
+/// Get the ABI functions of function definitions
+pub fn abi_functions<'a>(
+    functions: impl Iterator<Item = &'a FunctionDefinition>,
+) -> Result<Vec<Function>, RunnerError> {
+    functions
+        .map(|f| {
+            let signature = TestRunner::function_signature(f);
+            AbiParser::default().parse_function(&signature).map_err(|e| {
+                RunnerError(format!("Invalid function signature \"{signature}\": {e}"))
+            })
+        })
+        .collect()
+}
+
+/// Generate a random call to one of the given functions
+pub fn random_call(rng: &mut StdRng, functions: &[Function]) -> FuzzCall {
+    let function = functions[rng.gen_range(0..functions.len())].clone();
+    FuzzCall {
+        caller: Address::from_low_u64_be(FUZZ_CALLERS[rng.gen_range(0..FUZZ_CALLERS.len())]),
+        args: function.inputs.iter().map(|i| random_token(rng, &i.kind)).collect(),
+        function,
+    }
+}
+
+/// Generate a random value of an ABI type. Numbers are biased towards edge cases and small
+/// values, and addresses towards the campaign's callers.
+pub fn random_token(rng: &mut StdRng, kind: &ParamType) -> Token {
+    match kind {
+        ParamType::Address => Token::Address(if rng.gen_bool(0.5) {
+            Address::from_low_u64_be(FUZZ_CALLERS[rng.gen_range(0..FUZZ_CALLERS.len())])
+        } else {
+            Address::from(rng.gen::<[u8; 20]>())
+        }),
+        ParamType::Uint(bits) => Token::Uint(random_uint(rng, *bits)),
+        ParamType::Int(bits) => {
+            // Sign extend the value to 256 bits
+            let value = random_uint(rng, *bits);
+            if *bits < 256 && value.bit(bits - 1) {
+                Token::Int(value | !max_uint(*bits))
+            } else {
+                Token::Int(value)
+            }
+        }
+        ParamType::Bool => Token::Bool(rng.gen()),
+        ParamType::FixedBytes(n) => Token::FixedBytes((0..*n).map(|_| rng.gen()).collect()),
+        ParamType::Bytes => {
+            let len = rng.gen_range(0..=64);
+            Token::Bytes((0..len).map(|_| rng.gen()).collect())
+        }
+        ParamType::String => {
+            let len = rng.gen_range(0..=32);
+            Token::String((0..len).map(|_| rng.sample(Alphanumeric) as char).collect())
+        }
+        ParamType::Array(kind) => {
+            let len = rng.gen_range(0..=4);
+            Token::Array((0..len).map(|_| random_token(rng, kind)).collect())
+        }
+        ParamType::FixedArray(kind, n) => {
+            Token::FixedArray((0..*n).map(|_| random_token(rng, kind)).collect())
+        }
+        ParamType::Tuple(kinds) => {
+            Token::Tuple(kinds.iter().map(|k| random_token(rng, k)).collect())
+        }
+    }
+}
+
+/// The largest unsigned integer of the given number of bits
+fn max_uint(bits: usize) -> U256 {
+    if bits >= 256 {
+        U256::MAX
+    } else {
+        (U256::one() << bits) - 1
+    }
+}
+
+/// Generate a random unsigned integer of the given number of bits
+fn random_uint(rng: &mut StdRng, bits: usize) -> U256 {
+    let max = max_uint(bits);
+    match rng.gen_range(0..10) {
+        0 => U256::zero(),
+        1 => max,
+        2..=4 => U256::from(rng.gen_range(1u64..=1000)) & max,
+        _ => U256::from_big_endian(&rng.gen::<[u8; 32]>()) & max,
+    }
+}
@@ -1,9 +1,12 @@
-use crate::cheats::{HuffCheatCode, HUFF_CHEATS_MAP};
+use crate::{
+    cheats::{HuffCheatCode, HUFF_CHEATS_MAP},
+    inspectors::{coverage_inspector::CoverageInspector, trace_inspector::TraceInspector},
+};
 use bytes::Bytes;
 use ethers_core::{types::Address, utils::hex};
 use lazy_static::lazy_static;
 use revm::{
-    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult},
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
     primitives::B160,
     Database, EVMData, Inspector,
 };
@@ -17,12 +20,48 @@ lazy_static! {
 #[derive(Debug, Default)]
 pub struct CheatsInspector {
     pub logs: Vec<(u32, String)>,
+    /// An optional tracer that execution steps are forwarded to
+    pub tracer: Option<TraceInspector>,
+    /// An optional coverage inspector that execution steps are forwarded to
+    pub coverage: Option<CoverageInspector>,
+    /// An optional transaction origin that differs from the caller of the transaction
+    pub origin: Option<B160>,
+    /// The caller of the transaction, while it is replaced by the origin
+    pub caller: Option<B160>,
 }
 
 impl<DB> Inspector<DB> for CheatsInspector
 where
     DB: Database,
 {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+    ) -> InstructionResult {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.step(interp, data, is_static);
+        }
+        match &mut self.tracer {
+            Some(tracer) => tracer.step(interp, data, is_static),
+            None => InstructionResult::Continue,
+        }
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        is_static: bool,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        match &mut self.tracer {
+            Some(tracer) => tracer.step_end(interp, data, is_static, eval),
+            None => InstructionResult::Continue,
+        }
+    }
+
     fn log(
         &mut self,
         _: &mut EVMData<'_, DB>,
@@ -35,22 +74,36 @@ where
 
     fn call(
         &mut self,
-        _data: &mut EVMData<'_, DB>,
+        data: &mut EVMData<'_, DB>,
         inputs: &mut CallInputs,
         _is_static: bool,
     ) -> (InstructionResult, Gas, Bytes) {
+        // The `ORIGIN` opcode reads the caller of the transaction environment, which can only be
+        // changed once the transaction has been validated against its caller. It is replaced for
+        // the duration of the outermost call, so code reading the environment's caller during the
+        // call sees the origin as well.
+        if let Some(origin) = self.origin.filter(|_| data.journaled_state.depth() == 0) {
+            self.caller = Some(std::mem::replace(&mut data.env.tx.caller, origin));
+        }
         (InstructionResult::Continue, Gas::new(inputs.gas_limit), Bytes::new())
     }
 
     fn call_end(
         &mut self,
-        _data: &mut EVMData<'_, DB>,
+        data: &mut EVMData<'_, DB>,
         call: &CallInputs,
         remaining_gas: Gas,
         status: InstructionResult,
         out: Bytes,
         _is_static: bool,
     ) -> (InstructionResult, Gas, Bytes) {
+        // Restore the caller once the outermost call has ended, so that the transaction is
+        // settled against it
+        if data.journaled_state.depth() == 0 {
+            if let Some(caller) = self.caller.take() {
+                data.env.tx.caller = caller;
+            }
+        }
         let revm_cheats = revm::primitives::B160::from_slice(CHEATS_ADDR.as_bytes());
         if call.contract == revm_cheats && call.input.len() >= 64 {
             // All cheatcodes calls must include the cheatcode key and the current pc in the first
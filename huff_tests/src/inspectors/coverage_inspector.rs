@@ -0,0 +1,32 @@
+use revm::{
+    interpreter::{InstructionResult, Interpreter},
+    Database, EVMData, Inspector,
+};
+use std::collections::BTreeMap;
+
+/// An inspector that counts the executions of each pc within the called contract.
+#[derive(Debug, Default)]
+pub struct CoverageInspector {
+    pub hits: BTreeMap<usize, usize>,
+    depth: Option<u64>,
+}
+
+impl<DB> Inspector<DB> for CoverageInspector
+where
+    DB: Database,
+{
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> InstructionResult {
+        // Only steps at the depth of the first step belong to the called contract
+        let depth = *self.depth.get_or_insert(data.journaled_state.depth());
+        if data.journaled_state.depth() == depth {
+            *self.hits.entry(interp.program_counter()).or_default() += 1;
+        }
+
+        InstructionResult::Continue
+    }
+}
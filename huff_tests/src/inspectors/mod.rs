@@ -1,2 +1,8 @@
 /// The Cheatcodes inspector
 pub mod cheats_inspector;
+
+/// The execution trace inspector
+pub mod trace_inspector;
+
+/// The code coverage inspector
+pub mod coverage_inspector;
@@ -0,0 +1,80 @@
+use crate::types::TraceStep;
+use ethers_core::{types::U256, utils::hex};
+use revm::{
+    interpreter::{opcode, InstructionResult, Interpreter, OPCODE_JUMPMAP},
+    primitives::U256 as RU256,
+    Database, EVMData, Inspector,
+};
+
+/// Format a word of the stack or storage as hex, without leading zeros
+pub fn format_word(value: &RU256) -> String {
+    format!("{:#x}", U256::from(value.to_be_bytes::<32>()))
+}
+
+/// An inspector that records every step of execution.
+#[derive(Debug, Default)]
+pub struct TraceInspector {
+    pub steps: Vec<TraceStep>,
+    /// Whether to record the full stack and memory before each step, for the step debugger
+    pub snapshots: bool,
+}
+
+impl<DB> Inspector<DB> for TraceInspector
+where
+    DB: Database,
+{
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> InstructionResult {
+        let op = interp.current_opcode();
+
+        // Memory writes are recorded from the stack inputs before the opcode is executed
+        let memory_write = match (op, interp.stack.peek(0), interp.stack.peek(1)) {
+            (opcode::MSTORE, Ok(offset), Ok(value)) => {
+                Some((offset.saturating_to::<usize>(), format!("{value:064x}")))
+            }
+            (opcode::MSTORE8, Ok(offset), Ok(value)) => {
+                Some((offset.saturating_to::<usize>(), format!("{:02x}", value.byte(0))))
+            }
+            _ => None,
+        };
+        let storage_write = match (op, interp.stack.peek(0), interp.stack.peek(1)) {
+            (opcode::SSTORE, Ok(slot), Ok(value)) => {
+                Some((format_word(&slot), format_word(&value)))
+            }
+            _ => None,
+        };
+
+        self.steps.push(TraceStep {
+            depth: data.journaled_state.depth(),
+            pc: interp.program_counter(),
+            opcode: OPCODE_JUMPMAP[op as usize].unwrap_or("INVALID").to_string(),
+            memory_write,
+            storage_write,
+            stack: self
+                .snapshots
+                .then(|| interp.stack.data().iter().rev().map(format_word).collect()),
+            memory: self.snapshots.then(|| hex::encode(interp.memory.data())),
+            ..Default::default()
+        });
+
+        InstructionResult::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+        _eval: InstructionResult,
+    ) -> InstructionResult {
+        if let Some(step) = self.steps.last_mut() {
+            step.stack_top = interp.stack.peek(0).ok().map(|v| format!("{v:#x}"));
+        }
+
+        InstructionResult::Continue
+    }
+}
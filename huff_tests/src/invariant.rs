@@ -0,0 +1,182 @@
+use crate::prelude::{
+    abi_functions, random_call, FuzzCall, FuzzConfig, RunnerError, TestResult, TestRunner,
+    TestStatus,
+};
+use ethers_core::{abi::Function, types::Address};
+use huff_utils::{
+    ast::{DecoratorFlag, FunctionType, MacroDefinition},
+    prelude::Contract,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use revm::{primitives::ExecutionResult, InMemoryDB};
+
+/// Whether a test macro is marked as an invariant with the `invariant` decorator flag
+pub fn is_invariant(m: &MacroDefinition) -> bool {
+    m.decorator.as_ref().is_some_and(|d| d.flags.contains(&DecoratorFlag::Invariant))
+}
+
+/// Get the functions of a contract that an invariant campaign calls. Functions that cannot
+/// change state, i.e. `view` and `pure` functions, are left out.
+pub fn target_functions(contract: &Contract) -> Result<Vec<Function>, RunnerError> {
+    abi_functions(
+        contract
+            .functions
+            .iter()
+            .filter(|f| !matches!(f.fn_type, FunctionType::View | FunctionType::Pure)),
+    )
+}
+
+impl TestRunner {
+    /// Run an invariant testing campaign.
+    ///
+    /// The contract is deployed, and random sequences of calls are made to its functions. After
+    /// each successful call, every invariant test is run in place of the contract so that it can
+    /// read its state. The sequences of calls that break an invariant are minimized and
+    /// returned as the counterexample of the failing invariant's result.
+    pub fn run_invariants(
+        &mut self,
+        invariants: &[&MacroDefinition],
+        contract: &Contract,
+        config: &FuzzConfig,
+    ) -> Result<Vec<TestResult>, RunnerError> {
+        let functions = target_functions(contract)?;
+        let target = self.deploy_main(contract)?;
+        let initial = self.database.clone();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+
+        // The invariants must hold for the freshly deployed contract
+        let mut results = invariants
+            .iter()
+            .map(|m| {
+                self.check_invariant(m, contract, target).map(|mut res| {
+                    if let TestStatus::Revert = res.status {
+                        res.counterexample = Some(Vec::default());
+                    }
+                    res
+                })
+            })
+            .collect::<Result<Vec<TestResult>, RunnerError>>()?;
+        if functions.is_empty() {
+            return Ok(results)
+        }
+
+        for _ in 0..config.runs {
+            if results.iter().all(|r| matches!(r.status, TestStatus::Revert)) {
+                break
+            }
+
+            self.database = initial.clone();
+            let mut sequence = Vec::new();
+            for _ in 0..config.depth {
+                // Reverted calls leave the state untouched, so there is nothing to check
+                let call = random_call(&mut rng, &functions);
+                if !self.apply_call(&call, target)? {
+                    continue
+                }
+                sequence.push(call);
+
+                for (m, result) in invariants.iter().zip(results.iter_mut()) {
+                    if let TestStatus::Revert = result.status {
+                        continue
+                    }
+                    let res = self.check_invariant(m, contract, target)?;
+                    *result = match res.status {
+                        TestStatus::Success => res,
+                        TestStatus::Revert => {
+                            let state = self.database.clone();
+                            let res =
+                                self.shrink(m, contract, target, &initial, sequence.clone())?;
+                            self.database = state;
+                            res
+                        }
+                    };
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Run an invariant test in place of the contract under test, leaving its state untouched.
+    pub fn check_invariant(
+        &mut self,
+        m: &MacroDefinition,
+        contract: &Contract,
+        target: Address,
+    ) -> Result<TestResult, RunnerError> {
+        let state = self.database.clone();
+        self.fixture = Some((target, state.clone()));
+        let res = self.run_test(m, contract);
+        (self.database, self.fixture) = (state, None);
+        res
+    }
+
+    /// Make a call to the contract under test, returning whether it succeeded. Calls that
+    /// revert or halt both count as failed.
+    fn apply_call(&mut self, call: &FuzzCall, target: Address) -> Result<bool, RunnerError> {
+        let data =
+            call.function.encode_input(&call.args).map_err(|e| RunnerError(e.to_string()))?;
+        let res = self.transact(call.caller, target, data.into())?;
+        Ok(matches!(res, ExecutionResult::Success { .. }))
+    }
+
+    /// Replay a sequence of calls against the initial state, checking an invariant after each
+    /// call. Returns the number of calls after which the invariant broke, and its result.
+    fn replay(
+        &mut self,
+        m: &MacroDefinition,
+        contract: &Contract,
+        target: Address,
+        initial: &InMemoryDB,
+        sequence: &[FuzzCall],
+    ) -> Result<Option<(usize, TestResult)>, RunnerError> {
+        self.database = initial.clone();
+        for (i, call) in sequence.iter().enumerate() {
+            if !self.apply_call(call, target)? {
+                continue
+            }
+            let res = self.check_invariant(m, contract, target)?;
+            if let TestStatus::Revert = res.status {
+                return Ok(Some((i + 1, res)))
+            }
+        }
+        Ok(None)
+    }
+
+    /// Minimize a sequence of calls that breaks an invariant by removing every call that is
+    /// not needed to break it, returning the invariant's result with the minimized sequence
+    /// as its counterexample.
+    fn shrink(
+        &mut self,
+        m: &MacroDefinition,
+        contract: &Contract,
+        target: Address,
+        initial: &InMemoryDB,
+        mut sequence: Vec<FuzzCall>,
+    ) -> Result<TestResult, RunnerError> {
+        let (len, mut res) =
+            self.replay(m, contract, target, initial, &sequence)?.ok_or_else(|| {
+                RunnerError(format!("Failed to replay the calls that broke {}", m.name))
+            })?;
+        sequence.truncate(len);
+
+        let mut shrunk = true;
+        while shrunk {
+            shrunk = false;
+            for i in (0..sequence.len()).rev() {
+                let mut candidate = sequence.clone();
+                candidate.remove(i);
+                if let Some((len, candidate_res)) =
+                    self.replay(m, contract, target, initial, &candidate)?
+                {
+                    candidate.truncate(len);
+                    (sequence, res) = (candidate, candidate_res);
+                    shrunk = true;
+                    break
+                }
+            }
+        }
+
+        res.counterexample = Some(sequence.iter().map(ToString::to_string).collect());
+        Ok(res)
+    }
+}
@@ -1,6 +1,14 @@
-use crate::{errors::RunnerError, runner::TestRunner, types::TestResult};
+use crate::{
+    errors::RunnerError,
+    filter::{test_path, TestFilter},
+    fuzz::FuzzConfig,
+    invariant::is_invariant,
+    runner::TestRunner,
+    types::TestResult,
+};
 use huff_utils::prelude::{Contract, MacroDefinition};
-use std::{borrow::Borrow, rc::Rc};
+use rayon::prelude::*;
+use std::{collections::BTreeMap, rc::Rc};
 
 /// The runner module
 pub mod runner;
@@ -20,9 +28,36 @@ pub mod types;
 /// The errors module
 pub mod errors;
 
+/// The gas snapshot module
+pub mod snapshot;
+
+/// The code coverage module
+pub mod coverage;
+
+/// The test filter module
+pub mod filter;
+
+/// The revert decoding module
+pub mod decode;
+
+/// The random call generation module
+pub mod fuzz;
+
+/// The invariant testing module
+pub mod invariant;
+
+/// The differential testing module
+pub mod differential;
+
+/// The step debugger module
+pub mod debugger;
+
 /// Prelude wraps all modules within the crate
 pub mod prelude {
-    pub use crate::{errors::*, inspectors::*, report::*, runner::*, types::*};
+    pub use crate::{
+        coverage::*, debugger::*, decode::*, differential::*, errors::*, filter::*, fuzz::*,
+        inspectors::*, invariant::*, report::*, runner::*, snapshot::*, types::*,
+    };
 }
 
 /// A vector of shared references to test macro definitions
@@ -42,39 +77,108 @@ pub struct HuffTester<'t> {
 
     /// The test runner
     pub runner: TestRunner,
+
+    /// The number of threads to run tests on. Defaults to the number of available cores.
+    pub jobs: Option<usize>,
 }
 
 /// HuffTester implementation
 impl<'t> HuffTester<'t> {
     /// Create a new instance of `HuffTester` from a contract's AST.
-    pub fn new(ast: &'t Contract, match_: Rc<Option<String>>) -> Self {
+    pub fn new(ast: &'t Contract, filter: Rc<TestFilter>) -> Self {
         Self {
             ast,
             macros: {
                 // Filter all macros within the AST for `test` macros only
                 let mut macros: TestMacros<'t> = ast.macros.iter().filter(|m| m.test).collect();
-                // If the match flag is present, only retain the test macro
-                // that was queried
-                if let Some(match_) = match_.borrow() {
-                    macros.retain(|m| m.name == *match_);
-                }
+                // Only retain the test macros selected by the filter
+                macros.retain(|m| filter.is_match(m));
                 macros
             },
             runner: TestRunner::default(),
+            jobs: None,
+        }
+    }
+
+    /// List the selected tests, grouped by the path of the file they are defined in
+    pub fn list(&self) -> BTreeMap<String, Vec<String>> {
+        let mut tests: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for m in &self.macros {
+            tests.entry(test_path(m).unwrap_or_default()).or_default().push(m.name.clone());
         }
+        tests
     }
 
-    /// Execute tests
+    /// Execute tests. Invariant tests are only run by
+    /// [HuffTester::execute_invariants](execute_invariants).
     pub fn execute(mut self) -> Result<Vec<TestResult>, RunnerError> {
+        self.macros.retain(|m| !is_invariant(m));
+
         // Check if any test macros exist
         if self.macros.is_empty() {
             return Err(RunnerError(String::from("No test macros found.")));
         }
 
-        // Execute our tests and return a vector of the results
-        self.macros
-            .into_iter()
-            .map(|macro_def| self.runner.run_test(macro_def, self.ast))
-            .collect::<Result<Vec<TestResult>, RunnerError>>()
+        // Run the `SETUP` macro, if one exists, to create the fixture shared by all tests
+        if let Some(setup) = self.ast.find_macro_by_name("SETUP") {
+            self.runner.run_setup(setup, self.ast)?;
+        }
+
+        // Execute our tests in parallel, each against its own copy of the runner so that no
+        // state is shared between them, and return a vector of the results in the order the
+        // tests were defined
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.jobs.unwrap_or_default())
+            .build()
+            .map_err(|e| RunnerError(e.to_string()))?;
+        let (runner, ast) = (&self.runner, self.ast);
+        pool.install(|| {
+            self.macros
+                .par_iter()
+                .map(|macro_def| runner.clone().run_test(macro_def, ast))
+                .collect::<Result<Vec<TestResult>, RunnerError>>()
+        })
+    }
+
+    /// Execute an invariant testing campaign against the contract, checking the invariant
+    /// tests after each call.
+    pub fn execute_invariants(
+        mut self,
+        config: &FuzzConfig,
+    ) -> Result<Vec<TestResult>, RunnerError> {
+        let invariants =
+            self.macros.iter().copied().filter(|m| is_invariant(m)).collect::<Vec<_>>();
+        if invariants.is_empty() {
+            return Err(RunnerError(String::from("No invariant tests found.")))
+        }
+
+        self.runner.run_invariants(&invariants, self.ast, config)
+    }
+
+    /// Run a single test with the full stack and memory recorded at every step, for the step
+    /// debugger. The `SETUP` macro, if one exists, is run first.
+    pub fn debug(mut self, name: &str) -> Result<TestResult, RunnerError> {
+        let m = self
+            .macros
+            .iter()
+            .copied()
+            .find(|m| m.name == name)
+            .ok_or_else(|| RunnerError(format!("No test named \"{name}\" found.")))?;
+
+        if let Some(setup) = self.ast.find_macro_by_name("SETUP") {
+            self.runner.run_setup(setup, self.ast)?;
+        }
+        self.runner.debug = true;
+        self.runner.run_test(m, self.ast)
+    }
+
+    /// Execute a differential testing campaign, comparing the contract against the creation
+    /// bytecode of a reference implementation.
+    pub fn execute_differential(
+        mut self,
+        reference: &[u8],
+        config: &FuzzConfig,
+    ) -> Result<Vec<TestResult>, RunnerError> {
+        self.runner.run_differential(self.ast, reference, config)
     }
 }
@@ -1,9 +1,16 @@
-use crate::prelude::{ReportKind, TestResult, TestStatus};
+use crate::prelude::{
+    CoverageReport, GasDelta, ReportKind, RunnerError, TestResult, TestStatus, TraceStep,
+};
 use comfy_table::{
     modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement,
     Row, Table,
 };
-use std::time::Instant;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
 use yansi::Paint;
 
 /// Print a report of the test results, formatted according to the `report_kind` parameter.
@@ -34,16 +41,42 @@ pub fn print_test_report(results: Vec<TestResult>, report_kind: ReportKind, star
             table.set_content_arrangement(ContentArrangement::DynamicFullWidth);
             table.set_width(120);
 
+            let mut traces = Vec::new();
+            let mut counterexamples = Vec::new();
             for result in results {
+                if let Some(trace) = result.trace {
+                    traces.push((result.name.clone(), trace));
+                }
+                if let Some(calls) = result.counterexample {
+                    counterexamples.push((result.name.clone(), calls, result.divergence));
+                }
                 table.add_row(Row::from(vec![
                     Cell::new(result.name).add_attribute(Attribute::Bold).fg(Color::Cyan),
-                    Cell::new(result.return_data.unwrap_or_else(|| String::from("None"))),
+                    Cell::new(
+                        result
+                            .revert_reason
+                            .or(result.return_data)
+                            .unwrap_or_else(|| String::from("None")),
+                    ),
                     Cell::new(result.gas.to_string()),
                     Cell::from(result.status),
                 ]));
             }
 
             println!("{table}");
+
+            for (name, calls, divergence) in counterexamples {
+                println!("{} {}", Paint::cyan("COUNTEREXAMPLE"), Paint::magenta(&name));
+                print_counterexample(&calls);
+                if let Some(divergence) = divergence {
+                    println!("{} {}", Paint::cyan("DIVERGENCE"), Paint::magenta(name));
+                    print_divergence(&divergence);
+                }
+            }
+            for (name, trace) in traces {
+                println!("{} {}", Paint::cyan("TRACE"), Paint::magenta(name));
+                print_trace(&trace);
+            }
         }
         ReportKind::List => {
             for result in results {
@@ -57,7 +90,10 @@ pub fn print_test_report(results: Vec<TestResult>, report_kind: ReportKind, star
 
                 let num_logs = result.logs.len().saturating_sub(1);
 
-                if let Some(return_data) = result.return_data {
+                if let Some(revert_reason) = result.revert_reason {
+                    println!("├─ {}", Paint::cyan("REVERT REASON"));
+                    println!("{} {revert_reason}", if num_logs == 0 { "╰─" } else { "├─" });
+                } else if let Some(return_data) = result.return_data {
                     println!("├─ {}", Paint::cyan("RETURN DATA"));
                     println!("{} {return_data}", if num_logs == 0 { "╰─" } else { "├─" });
                 }
@@ -75,13 +111,27 @@ pub fn print_test_report(results: Vec<TestResult>, report_kind: ReportKind, star
                         // ├╌
                     });
                 }
+
+                if let Some(calls) = result.counterexample {
+                    println!("├─ {}", Paint::cyan("COUNTEREXAMPLE"));
+                    print_counterexample(&calls);
+                }
+
+                if let Some(divergence) = result.divergence {
+                    println!("├─ {}", Paint::cyan("DIVERGENCE"));
+                    print_divergence(&divergence);
+                }
+
+                if let Some(trace) = result.trace {
+                    println!("├─ {}", Paint::cyan("TRACE"));
+                    print_trace(&trace);
+                }
             }
         }
-        ReportKind::JSON => {
-            if let Ok(o) = serde_json::to_string_pretty(&results) {
-                println!("{o}");
-            } else {
-                eprintln!("Error serializing test results into JSON.");
+        ReportKind::JSON | ReportKind::JUnit | ReportKind::TAP => {
+            match gen_test_report(&results, report_kind, start.elapsed()) {
+                Ok(o) => println!("{o}"),
+                Err(e) => eprintln!("{e}"),
             }
             return;
         }
@@ -94,3 +144,284 @@ pub fn print_test_report(results: Vec<TestResult>, report_kind: ReportKind, star
         Paint::magenta(format!("{:.4?}", start.elapsed()))
     );
 }
+
+/// Print the names of tests, grouped by the path of the file they are defined in.
+pub fn print_test_list(tests: &BTreeMap<String, Vec<String>>) {
+    for (path, names) in tests {
+        println!("{}", Paint::blue(path));
+        for (i, name) in names.iter().enumerate() {
+            println!("{} {name}", if i == names.len() - 1 { "╰─" } else { "├─" });
+        }
+    }
+    println!(
+        "➜ {} tests found in {} files.",
+        Paint::green(tests.values().map(|n| n.len()).sum::<usize>()),
+        Paint::yellow(tests.len())
+    );
+}
+
+/// Generate a machine readable report of the test results, formatted according to the
+/// `report_kind` parameter.
+pub fn gen_test_report(
+    results: &[TestResult],
+    report_kind: ReportKind,
+    elapsed: Duration,
+) -> Result<String, RunnerError> {
+    match report_kind {
+        ReportKind::JSON => serde_json::to_string_pretty(results)
+            .map_err(|_| RunnerError(String::from("Error serializing test results into JSON."))),
+        ReportKind::JUnit => Ok(gen_junit_report(results, elapsed)),
+        ReportKind::TAP => Ok(gen_tap_report(results)),
+        ReportKind::Table | ReportKind::List => Err(RunnerError(String::from(
+            "Only JSON, JUnit and TAP test reports can be generated.",
+        ))),
+    }
+}
+
+/// Write a machine readable report of the test results to the given path
+pub fn write_test_report(
+    results: &[TestResult],
+    report_kind: ReportKind,
+    elapsed: Duration,
+    path: impl AsRef<Path>,
+) -> Result<(), RunnerError> {
+    let report = gen_test_report(results, report_kind, elapsed)?;
+    fs::write(path.as_ref(), report).map_err(|e| {
+        RunnerError(format!("Failed to write test report to \"{}\": {e}", path.as_ref().display()))
+    })
+}
+
+/// Generate a JUnit XML report of the test results.
+///
+/// The gas consumed by each test is reported as a property, and failing tests carry their
+/// decoded revert reason or revert data in the failure message.
+pub fn gen_junit_report(results: &[TestResult], elapsed: Duration) -> String {
+    let n_failed = results.iter().filter(|r| matches!(r.status, TestStatus::Revert)).count();
+    let totals = format!(
+        "tests=\"{}\" failures=\"{n_failed}\" time=\"{:.4}\"",
+        results.len(),
+        elapsed.as_secs_f64()
+    );
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<testsuites name=\"huffc\" {totals}>\n"));
+    xml.push_str(&format!("  <testsuite name=\"huffc\" {totals}>\n"));
+    for result in results {
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"huffc\">\n",
+            escape_xml(&result.name)
+        ));
+        xml.push_str("      <properties>\n");
+        xml.push_str(&format!("        <property name=\"gas\" value=\"{}\"/>\n", result.gas));
+        xml.push_str("      </properties>\n");
+        if let TestStatus::Revert = result.status {
+            xml.push_str(&format!(
+                "      <failure message=\"{}\" type=\"Revert\"/>\n",
+                escape_xml(&failure_message(result))
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Generate a TAP version 13 report of the test results.
+///
+/// The gas consumed by each test, and the revert reason or data of failing tests, are
+/// reported in each test's YAML block.
+pub fn gen_tap_report(results: &[TestResult]) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", results.len());
+    for (i, result) in results.iter().enumerate() {
+        let ok = if let TestStatus::Success = result.status { "ok" } else { "not ok" };
+        tap.push_str(&format!("{ok} {} - {}\n", i + 1, result.name));
+        tap.push_str(&format!("  ---\n  gas: {}\n", result.gas));
+        if let TestStatus::Revert = result.status {
+            tap.push_str(&format!("  message: \"{}\"\n", escape_yaml(&failure_message(result))));
+        }
+        tap.push_str("  ...\n");
+    }
+    tap
+}
+
+/// The failure message of a reverted test
+fn failure_message(result: &TestResult) -> String {
+    if let Some(divergence) = &result.divergence {
+        return format!("Diverged from the reference: {}", divergence.join("; "))
+    }
+    match (&result.revert_reason, &result.return_data) {
+        (Some(reason), _) => format!("Reverted with {reason}"),
+        (None, Some(data)) => format!("Reverted with data: 0x{data}"),
+        (None, None) => String::from("Reverted"),
+    }
+}
+
+/// Escape a string for use in an XML attribute
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Escape a string for use in a double-quoted YAML scalar
+fn escape_yaml(s: &str) -> String {
+    s.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+/// Print a table of the gas deltas between a test run and a gas snapshot.
+///
+/// Returns `true` if any test regressed by more than `tolerance` percent.
+pub fn print_snapshot_report(deltas: &[GasDelta], tolerance: f64) -> bool {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_header(Row::from(vec![
+        Cell::new("Name").fg(Color::Magenta),
+        Cell::new("Snapshot").fg(Color::Yellow),
+        Cell::new("Gas").fg(Color::Cyan),
+        Cell::new("Δ").fg(Color::Blue),
+        Cell::new("Δ %").fg(Color::Blue),
+    ]));
+
+    let mut n_regressions = 0;
+    for delta in deltas {
+        let color = if delta.is_regression(tolerance) {
+            n_regressions += 1;
+            Color::Red
+        } else if delta.diff() < 0 {
+            Color::Green
+        } else {
+            Color::Reset
+        };
+        table.add_row(Row::from(vec![
+            Cell::new(&delta.name).add_attribute(Attribute::Bold).fg(Color::Cyan),
+            Cell::new(delta.previous.map(|p| p.to_string()).unwrap_or_else(|| String::from("New"))),
+            Cell::new(
+                delta.current.map(|c| c.to_string()).unwrap_or_else(|| String::from("Removed")),
+            ),
+            Cell::new(format!("{:+}", delta.diff())).fg(color),
+            Cell::new(format!("{:+.2}%", delta.percent())).fg(color),
+        ]));
+    }
+    println!("{table}");
+
+    if n_regressions > 0 {
+        println!(
+            "➜ {} tests regressed by more than {}% gas.",
+            Paint::red(n_regressions),
+            Paint::yellow(tolerance)
+        );
+    } else {
+        println!("➜ {}", Paint::green("No gas regressions."));
+    }
+    n_regressions > 0
+}
+
+/// Print a summary of a coverage report, with the statement coverage of each macro and the
+/// line coverage of each file.
+pub fn print_coverage_report(report: &CoverageReport) {
+    fn percent(covered: usize, total: usize) -> Cell {
+        let percent = if total == 0 { 100.0 } else { covered as f64 * 100.0 / total as f64 };
+        let color = match percent {
+            p if p >= 90.0 => Color::Green,
+            p if p >= 50.0 => Color::Yellow,
+            _ => Color::Red,
+        };
+        Cell::new(format!("{percent:.2}% ({covered}/{total})")).fg(color)
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_header(Row::from(vec![
+        Cell::new("File").fg(Color::Magenta),
+        Cell::new("Macro").fg(Color::Cyan),
+        Cell::new("Statements").fg(Color::Yellow),
+        Cell::new("Lines").fg(Color::Blue),
+    ]));
+
+    for (file, coverage) in &report.files {
+        for (name, m) in &coverage.macros {
+            table.add_row(Row::from(vec![
+                Cell::new(file),
+                Cell::new(name),
+                percent(m.covered, m.statements),
+                Cell::new(""),
+            ]));
+        }
+        let statements = coverage.macros.values().map(|m| m.statements).sum();
+        let covered = coverage.macros.values().map(|m| m.covered).sum();
+        table.add_row(Row::from(vec![
+            Cell::new(file).add_attribute(Attribute::Bold),
+            Cell::new("Total").add_attribute(Attribute::Bold),
+            percent(covered, statements),
+            percent(coverage.lines.values().filter(|h| **h > 0).count(), coverage.lines.len()),
+        ]));
+    }
+
+    println!("{table}");
+}
+
+/// Print the sequence of calls that broke an invariant.
+fn print_counterexample(calls: &[String]) {
+    if calls.is_empty() {
+        println!("╰─ Broken after deployment");
+    }
+    for (i, call) in calls.iter().enumerate() {
+        println!("{} {call}", if i == calls.len() - 1 { "╰─" } else { "├─" });
+    }
+}
+
+/// Print the differences between the outcomes of a Huff and a reference implementation.
+fn print_divergence(divergence: &[String]) {
+    for (i, diff) in divergence.iter().enumerate() {
+        println!("{} {diff}", if i == divergence.len() - 1 { "╰─" } else { "├─" });
+    }
+}
+
+/// Print an execution trace, annotating each step with the macro and source line that
+/// generated it whenever they change.
+fn print_trace(trace: &[TraceStep]) {
+    let mut source = None;
+    for (i, step) in trace.iter().enumerate() {
+        let step_source = (&step.macro_name, &step.location);
+        if step.macro_name.is_some() && source != Some(step_source) {
+            println!(
+                "│ {} {}",
+                Paint::magenta(step.macro_name.as_deref().unwrap_or_default()),
+                Paint::blue(step.location.as_deref().unwrap_or_default())
+            );
+            source = Some(step_source);
+        }
+
+        let mut line = format!(
+            "{}[{}: {}] {: <14}",
+            "  ".repeat(step.depth.saturating_sub(1) as usize),
+            Paint::magenta("PC"),
+            Paint::yellow(format!("{:#06x}", step.pc)),
+            step.opcode
+        );
+        if let Some(stack_top) = &step.stack_top {
+            line = format!("{line} {} {stack_top}", Paint::cyan("stack:"));
+        }
+        if let Some((offset, value)) = &step.memory_write {
+            line = format!("{line} {} 0x{value}", Paint::cyan(format!("mem[{offset:#x}]:")));
+        }
+        if let Some((slot, value)) = &step.storage_write {
+            line = format!("{line} {} {value}", Paint::cyan(format!("storage[{slot}]:")));
+        }
+        println!("{} {line}", if i == trace.len() - 1 { "╰─" } else { "├─" });
+    }
+}
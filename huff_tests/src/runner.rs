@@ -1,28 +1,54 @@
-use crate::prelude::{cheats_inspector::CheatsInspector, RunnerError, TestResult, TestStatus};
+use crate::prelude::{
+    cheats_inspector::CheatsInspector, coverage_inspector::CoverageInspector, decode_revert,
+    map_coverage, test_path, trace_inspector::TraceInspector, CoverageItem, RunnerError,
+    TestResult, TestStatus, TraceStep,
+};
 use bytes::Bytes;
 use ethers_core::{
+    abi::{AbiParser, Token},
     types::{Address, U256},
     utils::hex,
 };
 use huff_codegen::Codegen;
+use huff_core::Compiler;
 use huff_utils::{
-    ast::{DecoratorFlag, MacroDefinition},
-    prelude::{pad_n_bytes, CompilerError, Contract, EVMVersion},
+    ast::{DecoratorFlag, FunctionDefinition, MacroDefinition},
+    prelude::{
+        pad_n_bytes, Bytes as CodeBytes, CodegenErrorKind, CompilerError, Contract, EToken,
+        EVMVersion, FileSource, Remapper, SourceMap, SupportedEVMVersions,
+    },
 };
 use revm::{
-    db::DbAccount,
+    db::AccountState,
     primitives::{
-        BlockEnv, CfgEnv, CreateScheme, Env, ExecutionResult, Output, SpecId, TransactTo, TxEnv,
+        AccountInfo, Bytecode, CreateScheme, Env, ExecutionResult, Output, SpecId, TransactTo,
+        TxEnv, B160, B256, U256 as RU256,
     },
     Database, InMemoryDB, EVM,
 };
+use std::{path::Path, sync::Arc};
 
 /// The test runner allows execution of test macros within an in-memory REVM
 /// instance.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct TestRunner {
     pub database: InMemoryDB,
+    /// The base environment of every transaction, which test decorators are applied on top of
     pub env: Env,
+    /// The transaction origin of test calls, if it differs from the caller
+    pub origin: Option<Address>,
+    /// The trace verbosity. At `3`, execution traces are kept for failing tests. At `4`,
+    /// execution traces are kept for all tests.
+    pub verbosity: u8,
+    /// Whether to record the code coverage of tests.
+    pub coverage: bool,
+    /// Whether to record the full stack and memory at each step of execution for the step
+    /// debugger. Traces are kept for all tests when set.
+    pub debug: bool,
+    /// The address and state produced by the `SETUP` macro, restored before each test.
+    pub fixture: Option<(Address, InMemoryDB)>,
+    /// The EVM version that tests are compiled for and executed on
+    pub evm_version: EVMVersion,
 }
 
 impl TestRunner {
@@ -36,12 +62,13 @@ impl TestRunner {
         let db = self.db_mut();
 
         let revm_address = revm::primitives::B160::from_slice(address.as_bytes());
-        let mut account = match db.basic(revm_address) {
-            Ok(Some(info)) => DbAccount { info, ..Default::default() },
-            _ => DbAccount::new_not_existing(),
-        };
+        let account = db.load_account(revm_address).unwrap_or_else(|e| match e {});
+        // Accounts that have never been touched are loaded as non-existent, which would hide
+        // their balance from the EVM
+        if matches!(account.account_state, AccountState::NotExisting) {
+            account.account_state = AccountState::None;
+        }
         account.info.balance = amount.into();
-        db.insert_account_info(revm_address, account.info);
 
         self
     }
@@ -74,15 +101,21 @@ impl TestRunner {
         };
         let bootstrap = format!("{contract_size}80{contract_code_offset}3d393df3{code}");
 
+        // The following should never panic, as any potential compilation error
+        // as well as an uneven number of hex nibbles should be caught in the
+        // compilation process.
+        self.create(hex::decode(bootstrap).expect("Invalid hex").into())
+            .map_err(|_| RunnerError(String::from("Test deployment failed")))
+    }
+
+    /// Run creation bytecode in our REVM instance and return the created contract's address.
+    pub fn create(&mut self, code: Bytes) -> Result<Address, RunnerError> {
         let mut evm = EVM::new();
         self.set_balance(Address::zero(), U256::MAX);
         evm.env = self.build_env(
             Address::zero(),
             TransactTo::Create(CreateScheme::Create),
-            // The following should never panic, as any potential compilation error
-            // as well as an uneven number of hex nibbles should be caught in the
-            // compilation process.
-            hex::decode(bootstrap).expect("Invalid hex").into(),
+            code,
             U256::zero(),
         );
         evm.database(self.db_mut());
@@ -93,12 +126,113 @@ impl TestRunner {
         // Check if deployment was successful
         let address = match er {
             ExecutionResult::Success { output: Output::Create(_, Some(addr)), .. } => addr,
-            _ => return Err(RunnerError(String::from("Test deployment failed"))),
+            _ => return Err(RunnerError(String::from("Contract creation failed"))),
         };
         let ethers_address = ethers_core::types::Address::from_slice(address.as_bytes());
         Ok(ethers_address)
     }
 
+    /// Compile a contract's `MAIN` and `CONSTRUCTOR` macros and deploy it, returning its
+    /// address. The constructor is optional and receives no arguments.
+    pub fn deploy_main(&mut self, contract: &Contract) -> Result<Address, RunnerError> {
+        let main = Codegen::generate_main_bytecode(&self.evm_version, contract, None)
+            .map_err(CompilerError::CodegenError)?;
+        let missing = CodegenErrorKind::MissingMacroDefinition(String::from("CONSTRUCTOR"));
+        let (constructor, has_custom_bootstrap) =
+            match Codegen::generate_constructor_bytecode(&self.evm_version, contract, None) {
+                Ok(c) => c,
+                Err(e) if e.kind == missing => (String::default(), false),
+                Err(e) => return Err(CompilerError::CodegenError(e).into()),
+            };
+        let artifact = Codegen::new()
+            .churn(
+                Arc::new(FileSource::default()),
+                Vec::default(),
+                &main,
+                &constructor,
+                has_custom_bootstrap,
+            )
+            .map_err(CompilerError::CodegenError)?;
+
+        let code = hex::decode(artifact.bytecode).map_err(|e| RunnerError(e.to_string()))?;
+        self.create(code.into())
+    }
+
+    /// Compile the contract at the given path and deploy it with the given constructor
+    /// arguments, returning its address.
+    pub fn deploy_contract(&mut self, path: &str, args: &[String]) -> Result<Address, RunnerError> {
+        // Constructor arguments are encoded during compilation, which panics on invalid input
+        args.iter().try_for_each(|arg| EToken::try_from(arg.clone()).map(drop)).map_err(|e| {
+            RunnerError(format!("Invalid constructor argument for \"{path}\": {e}"))
+        })?;
+
+        let evm_version = self.evm_version.clone();
+        let compiler = Compiler::new(
+            &evm_version,
+            Arc::new(vec![path.to_string()]),
+            None,
+            None,
+            None,
+            Some(args.to_vec()),
+            None,
+            false,
+            false,
+        );
+
+        // Compile the contract without touching the artifacts directory
+        let paths = compiler.file_provider.transform_paths(&compiler.sources)?;
+        let file = Compiler::fetch_sources(paths, compiler.file_provider.clone())
+            .into_iter()
+            .next()
+            .ok_or_else(|| RunnerError(format!("No contract found at \"{path}\"")))??;
+        let file =
+            Compiler::recurse_deps(file, &Remapper::new("./"), compiler.file_provider.clone())
+                .map_err(|e| RunnerError(e.to_string()))?;
+        let artifact = compiler.gen_artifact(file)?;
+
+        let code = hex::decode(artifact.bytecode).map_err(|e| RunnerError(e.to_string()))?;
+        self.create(code.into()).map_err(|e| RunnerError(format!("Deploying \"{path}\": {}", e.0)))
+    }
+
+    /// Deploy the contracts requested by the `deploy` flags of a macro's decorator, returning
+    /// the bytecode that pushes their addresses onto the stack. Paths are resolved relative to
+    /// the macro's file, and the address of the first contract ends up on top of the stack.
+    pub fn deploy_contracts(&mut self, m: &MacroDefinition) -> Result<String, RunnerError> {
+        let dir = test_path(m)
+            .and_then(|p| Path::new(&p).parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        let addresses = m
+            .decorator
+            .iter()
+            .flat_map(|d| &d.flags)
+            .filter_map(|flag| match flag {
+                DecoratorFlag::Deploy(path, args) => Some((path, args)),
+                _ => None,
+            })
+            .map(|(path, args)| self.deploy_contract(&dir.join(path).to_string_lossy(), args))
+            .collect::<Result<Vec<Address>, RunnerError>>()?;
+        Ok(addresses.iter().rev().map(|a| format!("73{}", hex::encode(a))).collect())
+    }
+
+    /// Send a transaction calling a deployed contract, without inspecting its execution.
+    pub fn transact(
+        &mut self,
+        caller: Address,
+        address: Address,
+        data: Bytes,
+    ) -> Result<ExecutionResult, RunnerError> {
+        let mut evm = EVM::new();
+        self.set_balance(caller, U256::MAX);
+        evm.env = self.build_env(
+            caller,
+            TransactTo::Call(B160::from_slice(address.as_bytes())),
+            data,
+            U256::zero(),
+        );
+        evm.database(self.db_mut());
+        evm.transact_commit().map_err(RunnerError::from)
+    }
+
     /// Perform a call to a deployed contract
     pub fn call(
         &mut self,
@@ -109,7 +243,13 @@ impl TestRunner {
         data: String,
     ) -> Result<TestResult, RunnerError> {
         let mut evm = EVM::new();
-        let mut inspector = CheatsInspector::default();
+        let mut inspector = CheatsInspector {
+            tracer: (self.verbosity >= 3 || self.debug)
+                .then(|| TraceInspector { snapshots: self.debug, ..Default::default() }),
+            coverage: self.coverage.then(CoverageInspector::default),
+            origin: self.origin.map(|o| B160::from_slice(o.as_bytes())),
+            ..Default::default()
+        };
         self.set_balance(caller, U256::MAX);
         let revm_address = revm::primitives::B160::from_slice(address.as_bytes());
 
@@ -124,16 +264,21 @@ impl TestRunner {
         // Send our CALL transaction
         let er = evm.inspect_commit(&mut inspector).map_err(RunnerError::from)?;
 
-        // Extract execution params
+        // Extract execution params. Exceptional halts, e.g. on an invalid opcode, fail the test
+        // like a revert does.
         let gas_used = match er {
             ExecutionResult::Success { gas_used, .. } => gas_used,
             ExecutionResult::Revert { gas_used, .. } => gas_used,
-            _ => return Err(RunnerError(String::from("Unexpected transaction status"))),
+            ExecutionResult::Halt { gas_used, .. } => gas_used,
         };
         let status = match er {
             ExecutionResult::Success { .. } => TestStatus::Success,
             _ => TestStatus::Revert,
         };
+        let halt_reason = match &er {
+            ExecutionResult::Halt { reason, .. } => Some(format!("Halted: {reason:?}")),
+            _ => None,
+        };
 
         // Check if the transaction was successful
         let return_data = match er {
@@ -155,94 +300,311 @@ impl TestRunner {
                     Some(hex::encode(output))
                 }
             }
-            _ => return Err(RunnerError(String::from("Unexpected transaction status"))),
+            ExecutionResult::Halt { .. } => None,
         };
 
         // Return our test result
         // NOTE: We subtract 21000 gas from the gas result to account for the
         // base cost of the CALL.
-        Ok(TestResult { name, return_data, gas: gas_used - 21000, status, logs: inspector.logs })
+        Ok(TestResult {
+            name,
+            path: None,
+            return_data,
+            revert_reason: halt_reason,
+            counterexample: None,
+            divergence: None,
+            gas: gas_used - 21000,
+            status,
+            logs: inspector.logs,
+            trace: inspector.tracer.map(|t| t.steps),
+            coverage: inspector.coverage.map(|c| {
+                c.hits
+                    .into_iter()
+                    .map(|(pc, hits)| CoverageItem { pc, hits, ..Default::default() })
+                    .collect()
+            }),
+        })
     }
 
-    /// Compile a test macro and run it in an in-memory REVM instance.
-    pub fn run_test(
-        &mut self,
+    /// Compile a macro for the given EVM version, returning its bytecode and source map. The
+    /// bytecode is prefixed with `prefix`, which the macro's offsets account for.
+    pub fn compile_macro(
         m: &MacroDefinition,
         contract: &Contract,
-    ) -> Result<TestResult, RunnerError> {
-        // TODO: set to non default
-        let evm_version = EVMVersion::default();
-
-        let name = m.name.to_owned();
-
-        // Compile the passed test macro
-        match Codegen::macro_to_bytecode(
-            &evm_version,
+        evm_version: &EVMVersion,
+        prefix: &str,
+    ) -> Result<(String, SourceMap), RunnerError> {
+        // Compile the passed macro
+        let mut res = Codegen::macro_to_bytecode(
+            evm_version,
             m,
             contract,
             &mut vec![m],
-            0,
+            prefix.len() / 2,
             &mut Vec::default(),
             false,
             None,
-        ) {
-            // Generate table bytecode for compiled test macro
-            Ok(res) => match Codegen::gen_table_bytecode(res) {
-                Ok(bytecode) => {
-                    // Deploy compiled test macro
-                    let address = self.deploy_code(bytecode)?;
-
-                    // Set environment flags passed through the test decorator
-                    let mut data = String::default();
-                    let mut value = U256::zero();
-                    if let Some(decorator) = &m.decorator {
-                        for flag in &decorator.flags {
-                            match flag {
-                                DecoratorFlag::Calldata(s) => {
-                                    // Strip calldata of 0x prefix, if it is present.
-                                    data = if let Some(s) = s.strip_prefix("0x") {
-                                        s.to_owned()
-                                    } else {
-                                        s.to_owned()
-                                    };
-                                }
-                                DecoratorFlag::Value(v) => value = U256::from(v),
-                            }
-                        }
-                    }
+        )
+        .map_err(CompilerError::CodegenError)?;
+        let source_map = res.source_map.clone();
+        res.bytes.insert(0, (0, CodeBytes(prefix.to_string())));
+
+        // Generate table bytecode for the compiled macro
+        let bytecode = Codegen::gen_table_bytecode(res).map_err(CompilerError::CodegenError)?;
+        Ok((bytecode, source_map))
+    }
+
+    /// Compile and run a `SETUP` macro, snapshotting the resulting state as the fixture that
+    /// every subsequent test is run against.
+    pub fn run_setup(
+        &mut self,
+        m: &MacroDefinition,
+        contract: &Contract,
+    ) -> Result<(), RunnerError> {
+        let prefix = self.deploy_contracts(m)?;
+        let (bytecode, _) = TestRunner::compile_macro(m, contract, &self.evm_version, &prefix)?;
+        let address = self.deploy_code(bytecode)?;
 
-                    // Call the deployed test
-                    let res = self.call(name, Address::zero(), address, value, data)?;
-                    Ok(res)
+        let res = self.call(
+            m.name.to_owned(),
+            Address::zero(),
+            address,
+            U256::zero(),
+            String::default(),
+        )?;
+        if let TestStatus::Revert = res.status {
+            return Err(RunnerError(
+                match TestRunner::revert_reason(&res, contract).or(res.revert_reason) {
+                    Some(reason) => format!("{} macro reverted with {reason}", m.name),
+                    None => format!("{} macro reverted", m.name),
+                },
+            ))
+        }
+
+        self.fixture = Some((address, self.database.clone()));
+        Ok(())
+    }
+
+    /// Replace the code of an account, keeping its balance and storage.
+    pub fn set_code(&mut self, address: Address, code: String) -> Result<(), RunnerError> {
+        let revm_address = revm::primitives::B160::from_slice(address.as_bytes());
+        let code = hex::decode(code).map_err(|e| RunnerError(e.to_string()))?;
+
+        let db = self.db_mut();
+        let info = db.basic(revm_address).ok().flatten().unwrap_or_default();
+        db.insert_account_info(
+            revm_address,
+            AccountInfo::new(info.balance, info.nonce, Bytecode::new_raw(code.into())),
+        );
+        Ok(())
+    }
+
+    /// Compile a test macro and run it in an in-memory REVM instance.
+    pub fn run_test(
+        &mut self,
+        m: &MacroDefinition,
+        contract: &Contract,
+    ) -> Result<TestResult, RunnerError> {
+        let name = m.name.to_owned();
+
+        // If a fixture exists, the fixture's state is restored and the test is run in place of
+        // the `SETUP` macro so that it shares its storage.
+        let fixture = self.fixture.as_ref().map(|(address, database)| {
+            self.database = database.clone();
+            *address
+        });
+
+        // Deploy the contracts requested by the test before compiling it, as their addresses
+        // are pushed onto the stack ahead of the test's own code
+        let prefix = self.deploy_contracts(m)?;
+        let (bytecode, source_map) =
+            TestRunner::compile_macro(m, contract, &self.evm_version, &prefix)?;
+
+        // Deploy the compiled test macro
+        let address = match fixture {
+            Some(address) => {
+                self.set_code(address, bytecode)?;
+                address
+            }
+            None => self.deploy_code(bytecode)?,
+        };
+
+        // Apply the environment flags passed through the test decorator and call the deployed
+        // test. The base environment is restored once the test has been called.
+        let (base_env, base_origin) = (self.env.clone(), self.origin);
+        let res = self
+            .apply_decorator(m, contract)
+            .and_then(|(caller, value, data)| self.call(name, caller, address, value, data));
+        (self.env, self.origin) = (base_env, base_origin);
+        let mut res = res?;
+
+        // Only keep traces for failing tests, unless all traces were requested
+        if matches!(res.status, TestStatus::Success) && self.verbosity < 4 && !self.debug {
+            res.trace = None;
+        }
+        if let Some(trace) = &mut res.trace {
+            TestRunner::map_trace(trace, &source_map);
+        }
+        res.coverage = res.coverage.map(|c| map_coverage(&c, &source_map));
+        res.revert_reason = TestRunner::revert_reason(&res, contract).or(res.revert_reason);
+        res.path = test_path(m);
+        Ok(res)
+    }
+
+    /// Decode the revert reason of a reverted test, using the errors defined in the contract.
+    pub fn revert_reason(res: &TestResult, contract: &Contract) -> Option<String> {
+        match (&res.status, &res.return_data) {
+            (TestStatus::Revert, Some(data)) => {
+                decode_revert(&hex::decode(data).ok()?, &contract.errors)
+            }
+            _ => None,
+        }
+    }
+
+    /// Apply the flags of a test's decorator to the environment, returning the caller, value
+    /// and calldata of the test call.
+    fn apply_decorator(
+        &mut self,
+        m: &MacroDefinition,
+        contract: &Contract,
+    ) -> Result<(Address, U256, String), RunnerError> {
+        let mut data = String::default();
+        let mut value = U256::zero();
+        let mut caller = Address::zero();
+        if let Some(decorator) = &m.decorator {
+            for flag in &decorator.flags {
+                match flag {
+                    DecoratorFlag::Calldata(s) => {
+                        // Strip calldata of 0x prefix, if it is present.
+                        data = if let Some(s) = s.strip_prefix("0x") {
+                            s.to_owned()
+                        } else {
+                            s.to_owned()
+                        };
+                    }
+                    DecoratorFlag::CalldataAbi(signature, args) => {
+                        data = TestRunner::encode_calldata(signature, args, contract)?
+                    }
+                    // Contracts are deployed before the test is compiled
+                    DecoratorFlag::Deploy(_, _) => {}
+                    // Invariant tests are selected by the tester, and run like any other test
+                    DecoratorFlag::Invariant => {}
+                    DecoratorFlag::Value(v) => value = U256::from(v),
+                    DecoratorFlag::Caller(a) => caller = Address::from_slice(&a[12..]),
+                    DecoratorFlag::Origin(a) => self.origin = Some(Address::from_slice(&a[12..])),
+                    DecoratorFlag::Timestamp(t) => {
+                        self.env.block.timestamp = RU256::from_be_bytes(*t)
+                    }
+                    DecoratorFlag::Number(n) => self.env.block.number = RU256::from_be_bytes(*n),
+                    DecoratorFlag::ChainId(c) => self.env.cfg.chain_id = RU256::from_be_bytes(*c),
+                    DecoratorFlag::BaseFee(b) => {
+                        // The gas price may not be lower than the base fee
+                        self.env.block.basefee = RU256::from_be_bytes(*b);
+                        self.env.tx.gas_price = self.env.block.basefee;
+                    }
+                    DecoratorFlag::GasLimit(g) => {
+                        self.env.tx.gas_limit = RU256::from_be_bytes(*g).saturating_to();
+                        self.env.block.gas_limit = RU256::from_be_bytes(*g);
+                    }
+                    DecoratorFlag::Coinbase(a) => {
+                        self.env.block.coinbase = B160::from_slice(&a[12..])
+                    }
+                    DecoratorFlag::PrevRandao(r) => {
+                        self.env.block.prevrandao = Some(B256::from(*r));
+                        self.env.block.difficulty = RU256::from_be_bytes(*r);
+                    }
                 }
-                Err(e) => Err(CompilerError::CodegenError(e).into()),
-            },
-            Err(e) => Err(CompilerError::CodegenError(e).into()),
+            }
+        }
+        Ok((caller, value, data))
+    }
+
+    /// ABI encode a call to a function, given either its full signature or the name of a
+    /// function defined in the contract, and its arguments.
+    pub fn encode_calldata(
+        signature: &str,
+        args: &[String],
+        contract: &Contract,
+    ) -> Result<String, RunnerError> {
+        let signature = if signature.contains('(') {
+            signature.to_string()
+        } else {
+            let function =
+                contract.functions.iter().find(|f| f.name == signature).ok_or_else(|| {
+                    RunnerError(format!("Function \"{signature}\" is not defined"))
+                })?;
+            TestRunner::function_signature(function)
+        };
+
+        let function = AbiParser::default()
+            .parse_function(&signature)
+            .map_err(|e| RunnerError(format!("Invalid function signature \"{signature}\": {e}")))?;
+        if function.inputs.len() != args.len() {
+            return Err(RunnerError(format!(
+                "Function \"{signature}\" expects {} arguments, but {} were given",
+                function.inputs.len(),
+                args.len()
+            )))
+        }
+
+        let tokens = function
+            .inputs
+            .iter()
+            .zip(args)
+            .map(|(input, arg)| EToken::try_from_param_type(arg.clone(), &input.kind).map(|t| t.0))
+            .collect::<Result<Vec<Token>, String>>()
+            .map_err(RunnerError)?;
+        let calldata = function.encode_input(&tokens).map_err(|e| RunnerError(e.to_string()))?;
+        Ok(hex::encode(calldata))
+    }
+
+    /// Get the canonical signature of a function definition, e.g. `transfer(address,uint256)`.
+    pub fn function_signature(function: &FunctionDefinition) -> String {
+        let inputs = function
+            .inputs
+            .iter()
+            .map(|i| i.arg_type.clone().unwrap_or_default())
+            .collect::<Vec<String>>();
+        format!("{}({})", function.name, inputs.join(","))
+    }
+
+    /// Map the steps of an execution trace back to the macros and source lines that generated
+    /// them. Only steps executed within the test contract itself are mapped.
+    pub fn map_trace(trace: &mut [TraceStep], source_map: &SourceMap) {
+        let depth = trace.first().map(|s| s.depth).unwrap_or_default();
+        trace.iter_mut().filter(|s| s.depth == depth).for_each(|step| {
+            // The source map is ordered by offset, with inlined macros following the statement
+            // that invoked them, so the last entry at or before the pc is the most specific.
+            if let Some(entry) = source_map.iter().rev().find(|e| e.pc <= step.pc) {
+                step.macro_name = Some(entry.macro_name.clone());
+                step.label = entry.label.clone();
+                step.location = entry.span.0.first().and_then(|s| s.location());
+                step.source = entry.span.0.first().and_then(|s| s.source_line());
+            }
+        });
+    }
+
+    /// Get the revm specification of an EVM version.
+    pub fn spec_id(evm_version: &EVMVersion) -> SpecId {
+        match evm_version.version() {
+            SupportedEVMVersions::Paris => SpecId::MERGE,
+            SupportedEVMVersions::Shanghai => SpecId::SHANGHAI,
         }
     }
 
     /// Build an EVM transaction environment.
     fn build_env(&self, caller: Address, to: TransactTo, data: Bytes, value: U256) -> Env {
         let revm_address = revm::primitives::B160::from_slice(caller.as_bytes());
-        Env {
-            cfg: CfgEnv {
-                chain_id: revm::primitives::U256::from(1),
-                spec_id: SpecId::LATEST,
-                ..Default::default()
-            },
-            block: BlockEnv {
-                basefee: revm::primitives::U256::from(0),
-                gas_limit: U256::MAX.into(),
-                ..Default::default()
-            },
-            tx: TxEnv {
-                chain_id: 1.into(),
-                caller: revm_address,
-                transact_to: to,
-                data,
-                value: value.into(),
-                ..Default::default()
-            },
-        }
+        let mut env = self.env.clone();
+        env.cfg.spec_id = TestRunner::spec_id(&self.evm_version);
+        env.tx = TxEnv {
+            chain_id: Some(env.cfg.chain_id.saturating_to()),
+            caller: revm_address,
+            transact_to: to,
+            data,
+            value: value.into(),
+            ..env.tx
+        };
+        env
     }
 }
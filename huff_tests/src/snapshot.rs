@@ -0,0 +1,118 @@
+use crate::prelude::{RunnerError, TestResult};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+/// The default gas snapshot file name
+pub const SNAPSHOT_FILE: &str = ".gas-snapshot";
+
+/// A gas snapshot, mapping test names, qualified by the path of their file, to the gas they
+/// consumed
+pub type GasSnapshot = BTreeMap<String, u64>;
+
+/// The change in gas consumption of a single test against a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasDelta {
+    /// The name of the test, qualified by the path of its file
+    pub name: String,
+    /// The gas recorded in the snapshot, if the test was present
+    pub previous: Option<u64>,
+    /// The gas consumed by the current run, if the test was not removed
+    pub current: Option<u64>,
+}
+
+impl GasDelta {
+    /// The absolute change in gas consumption. New and removed tests have not changed.
+    pub fn diff(&self) -> i128 {
+        match (self.previous, self.current) {
+            (Some(previous), Some(current)) => current as i128 - previous as i128,
+            _ => 0,
+        }
+    }
+
+    /// The change in gas consumption as a percentage of the snapshot. Any increase from zero
+    /// gas is infinite.
+    pub fn percent(&self) -> f64 {
+        match self.previous {
+            Some(0) if self.diff() > 0 => f64::INFINITY,
+            Some(0) | None => 0.0,
+            Some(previous) => self.diff() as f64 * 100.0 / previous as f64,
+        }
+    }
+
+    /// Whether the test consumes more gas than the snapshot allows, given a tolerance
+    /// percentage
+    pub fn is_regression(&self, tolerance: f64) -> bool {
+        self.diff() > 0 && self.percent() > tolerance
+    }
+}
+
+/// The name of a test in a gas snapshot, `path::NAME`, so that tests of the same name in
+/// different files are told apart
+pub fn snapshot_name(result: &TestResult) -> String {
+    match &result.path {
+        Some(path) => format!("{path}::{}", result.name),
+        None => result.name.clone(),
+    }
+}
+
+/// Create a gas snapshot from a set of test results
+pub fn gen_snapshot(results: &[TestResult]) -> GasSnapshot {
+    results.iter().map(|r| (snapshot_name(r), r.gas)).collect()
+}
+
+/// Write a gas snapshot of the test results to the given path.
+///
+/// Each test is written on its own line, sorted by name, as `path::NAME (gas: 1234)`.
+pub fn write_snapshot(results: &[TestResult], path: impl AsRef<Path>) -> Result<(), RunnerError> {
+    let contents = gen_snapshot(results)
+        .iter()
+        .map(|(name, gas)| format!("{name} (gas: {gas})\n"))
+        .collect::<String>();
+    fs::write(path.as_ref(), contents).map_err(|e| {
+        RunnerError(format!("Failed to write gas snapshot to \"{}\": {e}", path.as_ref().display()))
+    })
+}
+
+/// Read a gas snapshot from the given path
+pub fn read_snapshot(path: impl AsRef<Path>) -> Result<GasSnapshot, RunnerError> {
+    let contents = fs::read_to_string(path.as_ref()).map_err(|e| {
+        RunnerError(format!(
+            "Failed to read gas snapshot from \"{}\": {e}",
+            path.as_ref().display()
+        ))
+    })?;
+    contents
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            l.trim()
+                .rsplit_once(" (gas: ")
+                .and_then(|(name, gas)| {
+                    gas.strip_suffix(')')
+                        .and_then(|g| g.parse::<u64>().ok())
+                        .map(|g| (name.to_string(), g))
+                })
+                .ok_or_else(|| RunnerError(format!("Invalid gas snapshot entry: \"{l}\"")))
+        })
+        .collect()
+}
+
+/// Compare test results against a gas snapshot, returning the gas delta of each test, including
+/// the tests of the snapshot that were removed, sorted by name
+pub fn compare_snapshot(results: &[TestResult], snapshot: &GasSnapshot) -> Vec<GasDelta> {
+    let current = gen_snapshot(results);
+    current
+        .keys()
+        .chain(snapshot.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|name| GasDelta {
+            name: name.clone(),
+            previous: snapshot.get(name).copied(),
+            current: current.get(name).copied(),
+        })
+        .collect()
+}
@@ -1,3 +1,4 @@
+use crate::{coverage::CoverageItem, errors::RunnerError};
 use comfy_table::{Cell, Color};
 use serde::Serialize;
 use yansi::Paint;
@@ -6,10 +7,62 @@ use yansi::Paint;
 #[derive(Debug, Clone, Serialize)]
 pub struct TestResult {
     pub name: String,
+    /// The path of the file the test is defined in, if it is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
     pub return_data: Option<String>,
+    /// The decoded revert reason, if the test reverted with a known error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// The minimized sequence of calls that broke an invariant, if the test is a failing
+    /// invariant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counterexample: Option<Vec<String>>,
+    /// How the outcomes of the Huff and reference implementations differed, if the test is a
+    /// failing differential test
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub divergence: Option<Vec<String>>,
     pub gas: u64,
     pub status: TestStatus,
     pub logs: Vec<(u32, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<TraceStep>>,
+    #[serde(skip)]
+    pub coverage: Option<Vec<CoverageItem>>,
+}
+
+/// A single step of an execution trace
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TraceStep {
+    /// The call depth of the step
+    pub depth: u64,
+    /// The program counter
+    pub pc: usize,
+    /// The name of the executed opcode
+    pub opcode: String,
+    /// The top of the stack after the opcode was executed
+    pub stack_top: Option<String>,
+    /// The memory offset and value written by the opcode, if any
+    pub memory_write: Option<(usize, String)>,
+    /// The storage slot and value written by the opcode, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_write: Option<(String, String)>,
+    /// The stack before the opcode was executed, top first. Only recorded when debugging.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<String>>,
+    /// The hex encoded memory before the opcode was executed. Only recorded when debugging.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    /// The macro that generated the opcode
+    pub macro_name: Option<String>,
+    /// The label block that the opcode was generated within, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The `path:line` source location of the statement that generated the opcode
+    pub location: Option<String>,
+    /// The source line of the statement that generated the opcode
+    #[serde(skip)]
+    pub source: Option<String>,
 }
 
 /// A test status variant
@@ -20,6 +73,7 @@ pub enum TestStatus {
 }
 
 /// A test report kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReportKind {
     /// Signals `gen_report` to format the test report as a table
     Table,
@@ -27,6 +81,17 @@ pub enum ReportKind {
     List,
     /// Signals `gen_report` to format the test report as JSON
     JSON,
+    /// Signals `gen_report` to format the test report as JUnit XML
+    JUnit,
+    /// Signals `gen_report` to format the test report as TAP
+    TAP,
+}
+
+impl ReportKind {
+    /// Whether the report kind is meant to be read by other tools, rather than by humans
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(self, ReportKind::JSON | ReportKind::JUnit | ReportKind::TAP)
+    }
 }
 
 /// Convert a TestStatus variant directly to a colored string for use in the report
@@ -50,19 +115,25 @@ impl From<TestStatus> for Cell {
 }
 
 /// Convert a shared reference to an `Option<String>` to a `ReportKind`.
-/// If the `Option<String>` is `None` or there is no ReportKind match,
-/// `ReportKind::List` is returned.
-impl From<&Option<String>> for ReportKind {
-    fn from(str: &Option<String>) -> Self {
+/// If the `Option<String>` is `None`, `ReportKind::List` is returned. Unknown report kinds are
+/// rejected.
+impl TryFrom<&Option<String>> for ReportKind {
+    type Error = RunnerError;
+
+    fn try_from(str: &Option<String>) -> Result<Self, Self::Error> {
         if let Some(str) = str {
             match str.to_lowercase().as_str() {
-                "table" => ReportKind::Table,
-                "list" => ReportKind::List,
-                "json" => ReportKind::JSON,
-                _ => ReportKind::List,
+                "table" => Ok(ReportKind::Table),
+                "list" => Ok(ReportKind::List),
+                "json" => Ok(ReportKind::JSON),
+                "junit" => Ok(ReportKind::JUnit),
+                "tap" => Ok(ReportKind::TAP),
+                _ => Err(RunnerError(format!(
+                    "Invalid test report format \"{str}\". Expected list, table, json, junit or tap."
+                ))),
             }
         } else {
-            ReportKind::List
+            Ok(ReportKind::List)
         }
     }
 }
@@ -0,0 +1,115 @@
+use huff_tests::{
+    prelude::{TestFilter, TestRunner, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::{DecoratorFlag, EVMVersion};
+use std::rc::Rc;
+
+mod common;
+
+const SOURCE: &str = r#"
+#define function transfer(address,uint256) nonpayable returns (bool)
+#define function store(bytes) nonpayable returns ()
+
+#define macro ASSERT_TRANSFER_CALLDATA() = takes (0) returns (0) {
+    0x00 calldataload 0xe0 shr __FUNC_SIG(transfer) eq
+    0x04 calldataload 0xdead eq and
+    0x24 calldataload 0x64 eq and
+    calldatasize 0x44 eq and
+    ok jumpi
+    0x00 dup1 revert
+    ok:
+}
+
+#define macro ASSERT_STORE_CALLDATA() = takes (0) returns (0) {
+    0x00 calldataload 0xe0 shr __FUNC_SIG(store) eq
+    0x24 calldataload 0x02 eq and
+    0x44 calldataload 0xf0 shr 0x01 eq and
+    ok jumpi
+    0x00 dup1 revert
+    ok:
+}
+
+#[calldata_abi("transfer(address,uint256)", 0xdead, 100)]
+#define test SIGNATURE() = {
+    ASSERT_TRANSFER_CALLDATA()
+}
+
+#[calldata_abi("transfer", "0x000000000000000000000000000000000000dEaD", 0x64)]
+#define test FUNCTION_NAME() = {
+    ASSERT_TRANSFER_CALLDATA()
+}
+
+#[calldata_abi("store(bytes)", 0x0001)]
+#define test DYNAMIC_BYTES() = {
+    ASSERT_STORE_CALLDATA()
+}
+"#;
+
+#[test]
+fn test_calldata_abi_decorator() {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let contract = &contracts[0];
+
+    let flags = &contract.macros.iter().find(|m| m.name == "SIGNATURE").unwrap().decorator;
+    assert_eq!(
+        flags.as_ref().unwrap().flags,
+        vec![DecoratorFlag::CalldataAbi(
+            String::from("transfer(address,uint256)"),
+            vec![String::from("0xdead"), String::from("100")]
+        )]
+    );
+
+    // Literal arguments keep their leading zero bytes
+    let flags = &contract.macros.iter().find(|m| m.name == "DYNAMIC_BYTES").unwrap().decorator;
+    assert_eq!(
+        flags.as_ref().unwrap().flags,
+        vec![DecoratorFlag::CalldataAbi(
+            String::from("store(bytes)"),
+            vec![String::from("0x0001")]
+        )]
+    );
+
+    let results = HuffTester::new(contract, Rc::new(TestFilter::default())).execute().unwrap();
+    assert_eq!(results.len(), 3);
+    for result in results {
+        assert!(matches!(result.status, TestStatus::Success), "{} failed", result.name);
+    }
+}
+
+#[test]
+fn test_encode_calldata() {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let contract = &contracts[0];
+
+    let calldata = TestRunner::encode_calldata(
+        "transfer",
+        &[String::from("0xdead"), String::from("100")],
+        contract,
+    )
+    .unwrap();
+    assert_eq!(calldata, format!("a9059cbb{:0>64}{:0>64}", "dead", "64"),);
+
+    // Arguments are checked against the function's inputs
+    assert!(TestRunner::encode_calldata("transfer", &[String::from("0xdead")], contract).is_err());
+    assert!(TestRunner::encode_calldata(
+        "transfer(address,uint256)",
+        &[String::from("0x010000000000000000000000000000000000000000"), String::from("1")],
+        contract
+    )
+    .is_err());
+    assert!(TestRunner::encode_calldata("approve", &[], contract).is_err());
+
+    // Invalid hex arguments are rejected
+    assert!(TestRunner::encode_calldata(
+        "transfer",
+        &[String::from("0xzz"), String::from("1")],
+        contract
+    )
+    .is_err());
+    assert!(TestRunner::encode_calldata("store", &[String::from("0xzz")], contract).is_err());
+}
@@ -0,0 +1,21 @@
+use huff_core::Compiler;
+use huff_utils::prelude::EVMVersion;
+use std::{collections::HashMap, sync::Arc};
+
+/// Creates an in-memory compiler from `(path, source)` pairs, compiling the first file
+pub fn compiler<'a>(evm_version: &'a EVMVersion, sources: &[(&str, &str)]) -> Compiler<'a, 'a> {
+    let file_sources = sources
+        .iter()
+        .map(|(path, source)| (path.to_string(), source.to_string()))
+        .collect::<HashMap<String, String>>();
+    Compiler::new_in_memory(
+        evm_version,
+        Arc::new(vec![sources[0].0.to_string()]),
+        file_sources,
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+}
@@ -0,0 +1,82 @@
+use huff_core::Compiler;
+use huff_tests::{
+    prelude::{CoverageReport, TestFilter},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::{collections::HashMap, rc::Rc, sync::Arc};
+
+#[test]
+fn test_coverage_report() {
+    let main = r#"#include "./lib.huff"
+
+#define test PASSES() = {
+    0x01 CHECK()
+}
+"#;
+    let lib = r#"#define macro CHECK() = takes (1) returns (0) {
+    ok jumpi
+    0x00 0x00 revert
+    ok:
+        0x01
+        pop
+}
+
+#define macro UNUSED() = takes (0) returns (0) {
+    0x00 pop
+}
+"#;
+    // A macro sharing the test's name in another contract is not a test
+    let other = r#"#define macro PASSES() = takes (0) returns (0) {
+    0x00 pop
+}
+"#;
+
+    let evm_version = EVMVersion::default();
+    let mut file_sources = HashMap::new();
+    file_sources.insert(String::from("main.huff"), String::from(main));
+    file_sources.insert(String::from("lib.huff"), String::from(lib));
+    file_sources.insert(String::from("other.huff"), String::from(other));
+    let compiler = Compiler::new_in_memory(
+        &evm_version,
+        Arc::new(vec![String::from("main.huff"), String::from("other.huff")]),
+        file_sources,
+        None,
+        None,
+        None,
+        None,
+        false,
+    );
+    let contracts = compiler.grab_contracts().unwrap();
+
+    let mut tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    tester.runner.coverage = true;
+    let results = tester.execute().unwrap();
+
+    let report = CoverageReport::new(&contracts, &results);
+
+    // Test macros are excluded from the report
+    assert_eq!(report.files.keys().collect::<Vec<_>>(), vec!["lib.huff", "other.huff"]);
+    let passes = &report.files["other.huff"].macros["PASSES"];
+    assert_eq!((passes.line, passes.hits, passes.covered, passes.statements), (2, 0, 0, 2));
+    let coverage = &report.files["lib.huff"];
+
+    // The revert branch is never executed
+    assert_eq!(
+        coverage.lines.iter().map(|(l, h)| (*l, *h)).collect::<Vec<_>>(),
+        vec![(2, 1), (3, 0), (4, 1), (5, 1), (6, 1), (10, 0)]
+    );
+
+    let check = &coverage.macros["CHECK"];
+    assert_eq!((check.line, check.hits, check.covered, check.statements), (2, 1, 5, 8));
+    let unused = &coverage.macros["UNUSED"];
+    assert_eq!((unused.line, unused.hits, unused.covered, unused.statements), (10, 0, 0, 2));
+
+    let label = &coverage.labels[&(String::from("CHECK"), String::from("ok"))];
+    assert_eq!((label.line, label.hits, label.covered, label.statements), (4, 1, 3, 3));
+
+    let lcov = report.to_lcov();
+    assert!(lcov.starts_with("TN:\nSF:lib.huff\nFN:2,CHECK\nFN:10,UNUSED\nFNDA:1,CHECK\n"));
+    assert!(lcov.contains("BRDA:4,0,0,1\nBRF:1\nBRH:1\n"));
+    assert!(lcov.contains("LF:6\nLH:4\nend_of_record\nTN:\nSF:other.huff\nFN:2,PASSES\n"));
+}
@@ -0,0 +1,136 @@
+use huff_tests::{
+    prelude::{DebugCommand, Debugger, TestFilter},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+const SOURCE: &str = r#"
+#define macro STORE() = takes (1) returns (0) {
+    0x00 sstore    // []
+}
+
+#define test COUNT() = {
+    0x03           // [n]
+    loop:
+        0x01 swap1 sub      // [n - 1]
+        dup1 loop jumpi     // [n - 1]
+    0x2a STORE()
+    0x2a 0x00 mstore    // []
+}
+"#;
+
+fn debugger() -> Debugger {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    Debugger::new(tester.debug("COUNT").unwrap())
+}
+
+#[test]
+fn test_debug_records_steps() {
+    let debugger = debugger();
+    let step = debugger.current().unwrap();
+    assert_eq!(step.opcode, "PUSH1");
+    assert_eq!(step.macro_name.as_deref(), Some("COUNT"));
+    assert_eq!(step.location.as_deref(), Some("main.huff:7"));
+    assert_eq!(step.source.as_deref().map(str::trim), Some("0x03           // [n]"));
+    assert_eq!(step.stack, Some(Vec::new()));
+
+    // The full stack and memory are recorded at every step
+    let sstore = debugger.trace.iter().find(|s| s.opcode == "SSTORE").unwrap();
+    assert_eq!(sstore.stack.as_ref().unwrap(), &vec!["0x0", "0x2a", "0x0"]);
+    assert_eq!(sstore.storage_write, Some((String::from("0x0"), String::from("0x2a"))));
+    assert_eq!(sstore.macro_name.as_deref(), Some("STORE"));
+    let last = debugger.trace.last().unwrap();
+    assert_eq!(last.memory.as_deref(), Some(format!("{:064x}", 0x2a).as_str()));
+}
+
+#[test]
+fn test_debugger_commands() {
+    let mut debugger = debugger();
+
+    // Stepping executes a single opcode
+    assert_eq!(debugger.apply(&DebugCommand::Step), None);
+    assert_eq!(debugger.current().unwrap().opcode, "JUMPDEST");
+    assert_eq!(debugger.current().unwrap().label.as_deref(), Some("loop"));
+
+    // Next skips the rest of the current source line
+    debugger.apply(&DebugCommand::Next);
+    assert_eq!(debugger.current().unwrap().opcode, "PUSH1");
+    debugger.apply(&DebugCommand::Next);
+    assert_eq!(debugger.current().unwrap().opcode, "DUP1");
+    assert_eq!(debugger.current().unwrap().stack.as_ref().unwrap(), &vec!["0x2"]);
+
+    // Continue stops at every entry of a label with a breakpoint
+    assert_eq!(
+        debugger.apply(&DebugCommand::Break(String::from("missing"))).unwrap(),
+        "Label \"missing\" is never reached by the test"
+    );
+    debugger.apply(&DebugCommand::Break(String::from("loop")));
+    assert_eq!(
+        debugger.apply(&DebugCommand::Continue).unwrap(),
+        "Hit breakpoint on label \"loop\""
+    );
+    assert_eq!(debugger.current().unwrap().stack.as_ref().unwrap(), &vec!["0x2"]);
+    debugger.apply(&DebugCommand::Continue);
+    assert_eq!(debugger.current().unwrap().stack.as_ref().unwrap(), &vec!["0x1"]);
+
+    // Without further breakpoints, the execution runs to its end
+    assert_eq!(
+        debugger.apply(&DebugCommand::Continue).unwrap(),
+        "Execution finished: the test passed"
+    );
+    assert_eq!(debugger.position, debugger.trace.len() - 1);
+    assert_eq!(debugger.apply(&DebugCommand::Step).unwrap(), "Execution finished: the test passed");
+    assert_eq!(
+        debugger.apply(&DebugCommand::Break(String::from("loop"))).unwrap(),
+        "Removed breakpoint on label \"loop\""
+    );
+}
+
+#[test]
+fn test_debugger_render() {
+    yansi::Paint::disable();
+    let mut debugger = debugger();
+    while debugger.current().unwrap().opcode != "SSTORE" {
+        debugger.apply(&DebugCommand::Step);
+    }
+    let rendered = debugger.render();
+    assert!(rendered.contains("STORE main.huff:3"));
+    assert!(rendered.contains("╰─ 0x00 sstore    // []"));
+    assert!(rendered.contains("[PC: 0x000f] SSTORE (depth 1)"));
+    assert!(rendered.contains("STACK // []"));
+    assert!(rendered.contains("├─ 1: 0x2a"));
+
+    // Storage writes are shown once they have been executed
+    assert!(rendered.contains("STORAGE\n╰─ []"));
+    debugger.apply(&DebugCommand::Step);
+    assert!(debugger.render().contains("STORAGE\n╰─ 0x0: 0x2a"));
+}
+
+#[test]
+fn test_parse_debug_commands() {
+    assert_eq!("s".parse::<DebugCommand>().unwrap(), DebugCommand::Step);
+    assert_eq!(" next ".parse::<DebugCommand>().unwrap(), DebugCommand::Next);
+    assert_eq!("c".parse::<DebugCommand>().unwrap(), DebugCommand::Continue);
+    assert_eq!(
+        "break loop".parse::<DebugCommand>().unwrap(),
+        DebugCommand::Break(String::from("loop"))
+    );
+    assert_eq!("q".parse::<DebugCommand>().unwrap(), DebugCommand::Quit);
+    assert!("break".parse::<DebugCommand>().is_err());
+    assert!("jump 3".parse::<DebugCommand>().is_err());
+}
+
+#[test]
+fn test_debug_missing_test() {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    assert_eq!(tester.debug("MISSING").unwrap_err().0, "No test named \"MISSING\" found.");
+}
@@ -0,0 +1,90 @@
+use ethers_core::{
+    abi::{encode, Token},
+    types::U256,
+};
+use huff_tests::{
+    prelude::{decode_revert, gen_tap_report, TestFilter},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+const SOURCE: &str = r#"
+#define error Unauthorized(address account)
+#define error Insufficient(uint256, uint256)
+
+#define test CUSTOM() = {
+    __ERROR(Unauthorized) 0x00 mstore
+    0xc0ffee 0x04 mstore
+    0x24 0x00 revert
+}
+
+#define test ERROR_STRING() = {
+    0x08c379a0 0xe0 shl 0x00 mstore
+    0x20 0x04 mstore
+    0x04 0x24 mstore
+    0x626f6f6d 0xe0 shl 0x44 mstore
+    0x64 0x00 revert
+}
+
+#define test PANIC() = {
+    0x4e487b71 0xe0 shl 0x00 mstore
+    0x11 0x04 mstore
+    0x24 0x00 revert
+}
+
+#define test UNKNOWN() = {
+    0xdeadbeef 0xe0 shl 0x00 mstore
+    0x04 0x00 revert
+}
+"#;
+
+#[test]
+fn test_revert_reasons_are_decoded() {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    let results = tester.execute().unwrap();
+
+    let reasons = results.iter().map(|r| r.revert_reason.as_deref()).collect::<Vec<_>>();
+    assert_eq!(
+        reasons,
+        vec![
+            Some("Unauthorized(account=0x0000000000000000000000000000000000c0ffee)"),
+            Some("Error(\"boom\")"),
+            Some("Panic(0x11: arithmetic underflow or overflow)"),
+            None,
+        ]
+    );
+
+    // Undecoded revert data is still reported as hex
+    assert_eq!(results[3].return_data.as_deref(), Some("deadbeef"));
+
+    let report = gen_tap_report(&results);
+    assert!(
+        report.contains("message: \"Reverted with Panic(0x11: arithmetic underflow or overflow)\"")
+    );
+    assert!(report.contains("message: \"Reverted with data: 0xdeadbeef\""));
+}
+
+#[test]
+fn test_decode_unnamed_error_parameters() {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let error = contracts[0].errors.iter().find(|e| e.name == "Insufficient").unwrap();
+
+    let mut data = error.selector.to_vec();
+    data.extend(encode(&[Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]));
+    assert_eq!(
+        decode_revert(&data, &contracts[0].errors),
+        Some(String::from("Insufficient(uint256=1, uint256=2)"))
+    );
+
+    // Data that does not match the error's parameters is not decoded
+    assert_eq!(decode_revert(&data[..36], &contracts[0].errors), None);
+    assert_eq!(decode_revert(&data[..3], &contracts[0].errors), None);
+}
@@ -0,0 +1,85 @@
+use huff_core::Compiler;
+use huff_tests::{
+    prelude::{TestFilter, TestRunner, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::{fs, path::PathBuf, rc::Rc, sync::Arc};
+
+const TOKEN: &str = r#"
+#define macro CONSTRUCTOR() = takes (0) returns (0) {
+    // Store the constructor argument
+    0x20 dup1 codesize sub 0x00 codecopy
+    0x00 mload 0x00 sstore
+}
+
+#define macro MAIN() = takes (0) returns (0) {
+    0x00 sload 0x00 mstore
+    0x20 0x00 return
+}
+"#;
+
+const MAIN: &str = r#"
+#[deploy("./tokens/Token.huff", 0x2a), deploy("./tokens/Token.huff", 7)]
+#define test DEPLOYS() = {
+    // [first, second]
+    0x20 0x00 0x00 0x00 dup5 gas staticcall pop
+    0x00 mload 0x2a eq
+    0x20 0x00 0x00 0x00 dup7 gas staticcall pop
+    0x00 mload 0x07 eq and
+    success jumpi
+    0x00 dup1 revert
+    success:
+}
+
+#[deploy("./tokens/Missing.huff")]
+#define test MISSING() = {
+    0x01
+}
+"#;
+
+/// Write the test contract and its sibling to a fresh directory.
+fn write_sources(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("huff_tests_{name}_{}", std::process::id()));
+    fs::create_dir_all(dir.join("tokens")).unwrap();
+    fs::write(dir.join("main.huff"), MAIN).unwrap();
+    fs::write(dir.join("tokens").join("Token.huff"), TOKEN).unwrap();
+    dir
+}
+
+#[test]
+fn test_deploy_sibling_contracts() {
+    let dir = write_sources("deploy");
+    let evm_version = EVMVersion::default();
+    let compiler = Compiler::new(
+        &evm_version,
+        Arc::new(vec![dir.join("main.huff").to_string_lossy().to_string()]),
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        false,
+    );
+    let contracts = compiler.grab_contracts().unwrap();
+    let contract = &contracts[0];
+
+    let tester =
+        HuffTester::new(contract, Rc::new(TestFilter::new(Some("DEPLOYS"), None, None).unwrap()));
+    let results = tester.execute().unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0].status, TestStatus::Success));
+
+    // Contracts that cannot be compiled fail the test run
+    let m = contract.macros.iter().find(|m| m.name == "MISSING").unwrap();
+    let err = TestRunner::default().run_test(m, contract).unwrap_err();
+    assert!(err.0.contains("Missing.huff"));
+
+    // Invalid constructor arguments are reported instead of aborting the compilation
+    let token = dir.join("tokens").join("Token.huff").to_string_lossy().to_string();
+    let err = TestRunner::default().deploy_contract(&token, &[String::from("0xzz")]).unwrap_err();
+    assert!(err.0.starts_with("Invalid constructor argument"), "{}", err.0);
+
+    fs::remove_dir_all(dir).unwrap();
+}
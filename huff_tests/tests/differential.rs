@@ -0,0 +1,121 @@
+use ethers_core::utils::hex;
+use huff_tests::{
+    prelude::{read_reference_bytecode, FuzzConfig, TestFilter, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::{fs, rc::Rc};
+
+mod common;
+
+const INTERFACE: &str = r#"
+#define function arm() nonpayable returns ()
+#define function fire() nonpayable returns ()
+#define function get() view returns (uint256)
+
+#define constant COUNT = 0x00
+#define constant ARMED = 0x01
+"#;
+
+const MAIN: &str = r#"
+#define macro MAIN() = takes (0) returns (0) {
+    0x00 calldataload 0xe0 shr
+    dup1 __FUNC_SIG(arm) eq arm jumpi
+    dup1 __FUNC_SIG(fire) eq fire jumpi
+    dup1 __FUNC_SIG(get) eq get jumpi
+    0x00 dup1 revert
+
+    arm:
+        0x01 [ARMED] sstore
+        stop
+    fire:
+        [ARMED] sload iszero disarmed jumpi
+        [COUNT] sload 0x01 add [COUNT] sstore
+    disarmed:
+        stop
+    get:
+        [COUNT] sload 0x00 mstore
+        0x20 0x00 return
+}
+"#;
+
+const REFERENCE: &str = r#"
+#define macro MAIN() = takes (0) returns (0) {
+    0x00 calldataload 0xe0 shr
+    dup1 __FUNC_SIG(arm) eq arm jumpi
+    dup1 __FUNC_SIG(fire) eq fire jumpi
+    dup1 __FUNC_SIG(get) eq get jumpi
+    0x00 dup1 revert
+
+    arm:
+        0x01 [ARMED] sstore
+        stop
+    fire:
+        // Increments twice once armed
+        [ARMED] sload iszero disarmed jumpi
+        [COUNT] sload 0x02 add [COUNT] sstore
+    disarmed:
+        stop
+    get:
+        [COUNT] sload 0x00 mstore
+        0x20 0x00 return
+}
+"#;
+
+#[test]
+fn test_differential_campaign() {
+    let evm_version = EVMVersion::default();
+    let main = format!("{INTERFACE}{MAIN}");
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", &main)]).grab_contracts().unwrap();
+    let reference = format!("{INTERFACE}{REFERENCE}");
+    let artifacts = common::compiler(&evm_version, &[("main.huff", &reference)]).execute().unwrap();
+    let reference = hex::decode(&artifacts[0].bytecode).unwrap();
+
+    let tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    let config = FuzzConfig { runs: 32, depth: 10, seed: 1 };
+    let results = tester.execute_differential(&reference, &config).unwrap();
+
+    let names = results.iter().map(|r| r.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["arm()", "fire()", "get()"]);
+    assert!(matches!(results[0].status, TestStatus::Success));
+    assert!(matches!(results[2].status, TestStatus::Success));
+
+    // Functions report their most expensive call, here an armed `fire()` writing a fresh slot
+    assert!(results[1].gas > 20_000, "{}", results[1].gas);
+
+    // The diverging function is reported with its minimized counterexample
+    let fire = &results[1];
+    assert!(matches!(fire.status, TestStatus::Revert));
+    let calls = fire.counterexample.as_ref().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].starts_with("arm() from 0x"));
+    assert!(calls[1].starts_with("fire() from 0x"));
+    assert_eq!(
+        fire.divergence.as_ref().unwrap(),
+        &vec![String::from("Storage slot 0x0 differs: 0x1 (Huff) != 0x2 (reference)")]
+    );
+}
+
+#[test]
+fn test_read_reference_bytecode() {
+    let dir = std::env::temp_dir().join(format!("huff_tests_differential_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let artifacts = [
+        ("huffc.json", r#"{"file": {}, "bytecode": "6001"}"#),
+        ("foundry.json", r#"{"bytecode": {"object": "0x6001"}}"#),
+        ("solc.json", r#"{"abi": [], "evm": {"bytecode": {"object": "6001"}}}"#),
+        ("Reference.bin", "0x6001\n"),
+    ];
+    for (name, contents) in artifacts {
+        fs::write(dir.join(name), contents).unwrap();
+        assert_eq!(read_reference_bytecode(dir.join(name)).unwrap(), vec![0x60, 0x01]);
+    }
+
+    fs::write(dir.join("empty.json"), r#"{"abi": []}"#).unwrap();
+    assert!(read_reference_bytecode(dir.join("empty.json")).is_err());
+    assert!(read_reference_bytecode(dir.join("missing.json")).is_err());
+
+    fs::remove_dir_all(dir).unwrap();
+}
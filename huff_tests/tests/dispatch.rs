@@ -0,0 +1,116 @@
+use ethers_core::{
+    types::{Address, U256},
+    utils::hex,
+};
+use huff_codegen::Codegen;
+use huff_tests::prelude::{TestRunner, TestStatus};
+use huff_utils::prelude::{Contract, EVMVersion};
+
+mod common;
+
+const FUNCTIONS: [&str; 8] = [
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "approve(address,uint256)",
+    "allowance(address,address)",
+    "balanceOf(address)",
+    "totalSupply()",
+    "mint(address,uint256)",
+    "burn(uint256)",
+];
+
+/// Compiles a contract dispatching each function to a label returning its index
+fn compile(strategy: &str) -> Contract {
+    let mut source = FUNCTIONS
+        .iter()
+        .map(|f| format!("#define function {f} nonpayable returns ()\n"))
+        .collect::<String>();
+    source.push_str(&format!(
+        "#define macro MAIN() = takes (0) returns (0) {{\n    __DISPATCH({strategy})\n    0x00 dup1 revert\n"
+    ));
+    for (i, f) in FUNCTIONS.iter().enumerate() {
+        let name = &f[..f.find('(').unwrap()];
+        source.push_str(&format!(
+            "    {name}:\n        {:#04x} 0x00 mstore 0x20 0x00 return\n",
+            i + 1
+        ));
+    }
+    source.push('}');
+
+    let evm_version = EVMVersion::default();
+    let mut contracts =
+        common::compiler(&evm_version, &[("main.huff", &source)]).grab_contracts().unwrap();
+    contracts.remove(0)
+}
+
+#[test]
+fn test_dispatch_strategies() {
+    for strategy in ["linear", "binary", "packed_jumptable"] {
+        let contract = compile(strategy);
+        let mut runner = TestRunner::default();
+        let address = runner.deploy_main(&contract).unwrap();
+        let gas = Codegen::dispatch_gas(&runner.evm_version, &contract);
+        assert_eq!(gas.len(), FUNCTIONS.len());
+
+        for (i, f) in contract.functions.iter().enumerate() {
+            let calldata = hex::encode(f.signature);
+            let result = runner
+                .call(f.name.clone(), Address::zero(), address, U256::zero(), calldata)
+                .unwrap();
+            assert!(matches!(result.status, TestStatus::Success), "{strategy}: {}", f.name);
+            assert_eq!(result.return_data, Some(format!("{:064x}", i + 1)), "{strategy}");
+
+            // The call spends the calldata, the reported dispatch gas and the label's code, which
+            // expands memory unless the packed jump table lookup already did
+            let calldata_gas =
+                f.signature.iter().map(|b| if *b == 0 { 4 } else { 16 }).sum::<u64>();
+            let label_gas = if strategy == "packed_jumptable" { 13 } else { 16 };
+            assert_eq!(
+                result.gas,
+                calldata_gas + gas[&f.signature] + label_gas,
+                "{strategy}: {}",
+                f.name
+            );
+        }
+
+        // Unknown selectors fall through the dispatcher
+        for calldata in ["", "deadbeef", "00000000"] {
+            let result = runner
+                .call(
+                    String::from("UNKNOWN"),
+                    Address::zero(),
+                    address,
+                    U256::zero(),
+                    calldata.into(),
+                )
+                .unwrap();
+            assert!(matches!(result.status, TestStatus::Revert), "{strategy}: {calldata}");
+        }
+    }
+}
+
+#[test]
+fn test_dispatch_leaves_memory_clear() {
+    let source = "#define function transfer(address,uint256) nonpayable returns ()
+        #define macro MAIN() = takes (0) returns (0) {
+            __DISPATCH(packed_jumptable)
+            0x00 dup1 revert
+            transfer:
+                0x20 0x00 return
+        }";
+    let evm_version = EVMVersion::default();
+    let contract = common::compiler(&evm_version, &[("main.huff", source)])
+        .grab_contracts()
+        .unwrap()
+        .remove(0);
+    let mut runner = TestRunner::default();
+    let address = runner.deploy_main(&contract).unwrap();
+
+    // The scratch word the table entry is copied through is cleared before jumping to the label
+    let calldata = hex::encode(contract.functions[0].signature);
+    let result = runner
+        .call(String::from("transfer"), Address::zero(), address, U256::zero(), calldata)
+        .unwrap();
+    assert!(matches!(result.status, TestStatus::Success));
+    assert_eq!(result.return_data, Some("0".repeat(64)));
+}
@@ -0,0 +1,57 @@
+use huff_tests::{
+    prelude::{TestFilter, TestRunner, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+#[test]
+fn test_env_decorator_flags() {
+    let source = r#"
+#[caller(0xc0ffee), origin(0xbeef), timestamp(0x64), number(0x0a), chainid(0x05), basefee(0x07), gas_limit(0x0186a0), coinbase(0xca11), prevrandao(0x2a)]
+#define test ENV() = {
+    caller 0xc0ffee eq
+    origin 0xbeef eq and
+    timestamp 0x64 eq and
+    number 0x0a eq and
+    chainid 0x05 eq and
+    basefee 0x07 eq and
+    gas 0x0186a0 gt and
+    coinbase 0xca11 eq and
+    prevrandao 0x2a eq and
+    ok jumpi
+    0x00 dup1 revert
+    ok:
+}
+
+#define test DEFAULT_ENV() = {
+    caller iszero
+    origin iszero and
+    timestamp 0x01 eq and
+    chainid 0x01 eq and
+    ok jumpi
+    0x00 dup1 revert
+    ok:
+}
+"#;
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", source)]).grab_contracts().unwrap();
+    let contract = &contracts[0];
+
+    // Run the tests in sequence against a single runner, so that the environment of the
+    // decorated test must not leak into the next one
+    let mut runner = TestRunner::default();
+    for m in contract.macros.iter().filter(|m| m.test) {
+        let result = runner.run_test(m, contract).unwrap();
+        assert!(matches!(result.status, TestStatus::Success), "{} failed", result.name);
+    }
+
+    let results = HuffTester::new(contract, Rc::new(TestFilter::default())).execute().unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        assert!(matches!(result.status, TestStatus::Success), "{} failed", result.name);
+    }
+}
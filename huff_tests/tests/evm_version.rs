@@ -0,0 +1,57 @@
+use huff_tests::{
+    prelude::{TestFilter, TestRunner, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+#[test]
+fn test_runner_honors_evm_version() {
+    let source = r#"
+#define test ZERO() = {
+    0x00 0x00 mstore
+}
+"#;
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", source)]).grab_contracts().unwrap();
+    let contract = &contracts[0];
+    let m = &contract.macros[0];
+
+    let paris = EVMVersion::from(String::from("paris"));
+    let (bytecode, _) = TestRunner::compile_macro(m, contract, &paris, "").unwrap();
+    assert_eq!(bytecode, "6000600052");
+    let (bytecode, _) = TestRunner::compile_macro(m, contract, &EVMVersion::default(), "").unwrap();
+    assert_eq!(bytecode, "5f5f52");
+
+    let mut tester = HuffTester::new(contract, Rc::new(TestFilter::default()));
+    tester.runner.evm_version = paris;
+    tester.runner.verbosity = 4;
+    let results = tester.execute().unwrap();
+    assert!(matches!(results[0].status, TestStatus::Success));
+    let opcodes = results[0].trace.as_ref().unwrap().iter().map(|s| s.opcode.as_str());
+    assert_eq!(opcodes.collect::<Vec<_>>(), vec!["PUSH1", "PUSH1", "MSTORE", "STOP"]);
+}
+
+#[test]
+fn test_push0_is_not_activated_on_paris() {
+    let source = r#"
+#define test EXPLICIT_PUSH0() = {
+    push0 push0 mstore
+}
+"#;
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", source)]).grab_contracts().unwrap();
+
+    let mut tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    tester.runner.evm_version = EVMVersion::from(String::from("paris"));
+    let results = tester.execute().unwrap();
+    assert!(matches!(results[0].status, TestStatus::Revert));
+    assert_eq!(results[0].revert_reason.as_deref(), Some("Halted: NotActivated"));
+
+    let results = HuffTester::new(&contracts[0], Rc::new(TestFilter::default())).execute().unwrap();
+    assert!(matches!(results[0].status, TestStatus::Success));
+}
@@ -0,0 +1,63 @@
+use huff_tests::{prelude::TestFilter, HuffTester};
+use huff_utils::prelude::{Contract, EVMVersion};
+use std::rc::Rc;
+
+mod common;
+
+fn compile() -> Vec<Contract> {
+    let main = r#"#include "./erc20_tests.huff"
+
+#define test MAIN_ONE() = {}
+#define test MAIN_TWO() = {}
+"#;
+    let erc20_tests = r#"
+#define test ERC20_TRANSFER() = {}
+#define test ERC20_APPROVE() = {}
+"#;
+
+    let evm_version = EVMVersion::default();
+    let compiler =
+        common::compiler(&evm_version, &[("main.huff", main), ("erc20_tests.huff", erc20_tests)]);
+    compiler.grab_contracts().unwrap()
+}
+
+fn selected(contract: &Contract, filter: TestFilter) -> Vec<String> {
+    HuffTester::new(contract, Rc::new(filter)).macros.iter().map(|m| m.name.clone()).collect()
+}
+
+#[test]
+fn test_filter_tests() {
+    let contracts = compile();
+    let contract = &contracts[0];
+
+    // Exact names, globs and regexes all select by name
+    let filter = TestFilter::new(Some("MAIN_ONE"), None, None).unwrap();
+    assert_eq!(selected(contract, filter), vec!["MAIN_ONE"]);
+    let filter = TestFilter::new(Some("ERC20_*"), None, None).unwrap();
+    assert_eq!(selected(contract, filter), vec!["ERC20_TRANSFER", "ERC20_APPROVE"]);
+    let filter = TestFilter::new(Some("MAIN_(ONE|TWO)"), None, None).unwrap();
+    assert_eq!(selected(contract, filter), vec!["MAIN_ONE", "MAIN_TWO"]);
+
+    // Paths select by the file the test is defined in
+    let filter = TestFilter::new(None, Some("erc20_*.huff"), None).unwrap();
+    assert_eq!(selected(contract, filter), vec!["ERC20_TRANSFER", "ERC20_APPROVE"]);
+
+    // Skipped tests are excluded by name or path
+    let filter = TestFilter::new(None, None, Some(".*_TWO")).unwrap();
+    assert_eq!(selected(contract, filter), vec!["MAIN_ONE", "ERC20_TRANSFER", "ERC20_APPROVE"]);
+    let filter = TestFilter::new(Some(".*TRANSFER|MAIN_.*"), None, Some("main.huff")).unwrap();
+    assert_eq!(selected(contract, filter), vec!["ERC20_TRANSFER"]);
+
+    assert!(TestFilter::new(Some("[unclosed"), None, None).is_err());
+}
+
+#[test]
+fn test_list_tests() {
+    let contracts = compile();
+    let filter = TestFilter::new(None, None, Some("*_TWO")).unwrap();
+    let tests = HuffTester::new(&contracts[0], Rc::new(filter)).list();
+
+    assert_eq!(tests.len(), 2);
+    assert_eq!(tests["erc20_tests.huff"], vec!["ERC20_TRANSFER", "ERC20_APPROVE"]);
+    assert_eq!(tests["main.huff"], vec!["MAIN_ONE"]);
+}
@@ -0,0 +1,105 @@
+use huff_tests::{
+    prelude::{FuzzConfig, TestFilter, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+const SOURCE: &str = r#"
+#define function arm() nonpayable returns ()
+#define function fire() nonpayable returns ()
+#define function both(uint256) nonpayable returns ()
+#define function halt() nonpayable returns ()
+#define function a() view returns (uint256)
+
+#define constant A = FREE_STORAGE_POINTER()
+#define constant B = FREE_STORAGE_POINTER()
+#define constant ARMED = FREE_STORAGE_POINTER()
+
+#define macro MAIN() = takes (0) returns (0) {
+    0x00 calldataload 0xe0 shr
+    dup1 __FUNC_SIG(arm) eq arm jumpi
+    dup1 __FUNC_SIG(fire) eq fire jumpi
+    dup1 __FUNC_SIG(both) eq both jumpi
+    dup1 __FUNC_SIG(halt) eq halt jumpi
+    0x00 dup1 revert
+
+    arm:
+        0x01 [ARMED] sstore
+        stop
+    fire:
+        // Breaks the invariant once armed
+        [ARMED] sload iszero disarmed jumpi
+        [A] sload 0x01 add [A] sstore
+    disarmed:
+        stop
+    both:
+        0x04 calldataload dup1
+        [A] sload add [A] sstore
+        [B] sload add [B] sstore
+        stop
+    halt:
+        // Halting calls fail like reverting ones
+        invalid
+}
+
+#[invariant]
+#define test A_EQ_B() = {
+    [A] sload [B] sload eq ok jumpi
+    0x00 dup1 revert
+    ok:
+}
+
+#[invariant]
+#define test ARMED_IS_BOOL() = {
+    [ARMED] sload 0x02 gt ok jumpi
+    0x00 dup1 revert
+    ok:
+}
+
+#define test REGULAR() = {
+    0x01
+}
+"#;
+
+#[test]
+fn test_invariant_campaign() {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    let config = FuzzConfig { runs: 32, depth: 10, seed: 1 };
+    let results = tester.execute_invariants(&config).unwrap();
+
+    let names = results.iter().map(|r| r.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["A_EQ_B", "ARMED_IS_BOOL"]);
+
+    // The broken invariant is reported with its minimized counterexample
+    assert!(matches!(results[0].status, TestStatus::Revert));
+    let calls = results[0].counterexample.as_ref().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].starts_with("arm() from 0x"));
+    assert!(calls[1].starts_with("fire() from 0x"));
+
+    assert!(matches!(results[1].status, TestStatus::Success));
+    assert!(results[1].counterexample.is_none());
+}
+
+#[test]
+fn test_invariants_are_not_run_as_tests() {
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", SOURCE)]).grab_contracts().unwrap();
+    let tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    let results = tester.execute().unwrap();
+    assert_eq!(results.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), vec!["REGULAR"]);
+
+    // Without invariant tests, there is no campaign to run
+    let tester = HuffTester::new(
+        &contracts[0],
+        Rc::new(TestFilter::new(Some("REGULAR"), None, None).unwrap()),
+    );
+    assert!(tester.execute_invariants(&FuzzConfig::default()).is_err());
+}
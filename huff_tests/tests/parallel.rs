@@ -0,0 +1,92 @@
+use huff_tests::{
+    prelude::{TestFilter, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+#[test]
+fn test_parallel_tests_are_isolated_and_ordered() {
+    // Every test asserts that storage is empty before writing to it, so any state leaking
+    // between tests would cause a failure.
+    let mut source = String::from(
+        r#"
+#define macro WRITE_EMPTY_SLOT() = takes (0) returns (0) {
+    0x00 sload iszero ok jumpi
+    0x00 dup1 revert
+    ok:
+        0x01 0x00 sstore
+}
+"#,
+    );
+    let names = (0..64).map(|i| format!("TEST_{i}")).collect::<Vec<_>>();
+    names.iter().for_each(|name| {
+        source.push_str(&format!("\n#define test {name}() = {{\n    WRITE_EMPTY_SLOT()\n}}\n"))
+    });
+
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", &source)]).grab_contracts().unwrap();
+
+    for jobs in [Some(1), Some(4), None] {
+        let mut tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+        tester.jobs = jobs;
+        let results = tester.execute().unwrap();
+
+        assert_eq!(
+            results.iter().map(|r| &r.name).collect::<Vec<_>>(),
+            names.iter().collect::<Vec<_>>()
+        );
+        for result in results {
+            assert!(matches!(result.status, TestStatus::Success), "{} failed", result.name);
+        }
+    }
+}
+
+#[test]
+fn test_parallel_tests_do_not_share_setup_state() {
+    // Every test checks that the fixture slot still holds the value written by SETUP, then
+    // overwrites it with its own value and reads it back. A write from another test would
+    // change either value.
+    let mut source = String::from(
+        r#"
+#define macro SETUP() = takes (0) returns (0) {
+    0xff 0x00 sstore
+}
+
+#define macro OVERWRITE_FIXTURE() = takes (1) returns (0) {
+    // Input stack:         [value]
+    0x00 sload 0xff eq ok jumpi
+    0x00 dup1 revert
+    ok:
+        dup1 0x00 sstore    // [value]
+        0x00 sload eq written jumpi
+        0x00 dup1 revert
+    written:
+}
+"#,
+    );
+    let names = (0..64).map(|i| format!("TEST_{i}")).collect::<Vec<_>>();
+    names.iter().enumerate().for_each(|(i, name)| {
+        source.push_str(&format!(
+            "\n#define test {name}() = {{\n    {i:#04x} OVERWRITE_FIXTURE()\n}}\n"
+        ))
+    });
+
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", &source)]).grab_contracts().unwrap();
+
+    for jobs in [Some(4), None] {
+        let mut tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+        tester.jobs = jobs;
+        let results = tester.execute().unwrap();
+
+        assert_eq!(results.len(), names.len());
+        for result in results {
+            assert!(matches!(result.status, TestStatus::Success), "{} failed", result.name);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use huff_tests::prelude::{
+    gen_junit_report, gen_tap_report, gen_test_report, write_test_report, ReportKind, TestResult,
+    TestStatus,
+};
+use std::time::Duration;
+
+fn results() -> Vec<TestResult> {
+    vec![
+        TestResult {
+            name: String::from("PASSES"),
+            path: None,
+            return_data: None,
+            revert_reason: None,
+            counterexample: None,
+            divergence: None,
+            gas: 42,
+            status: TestStatus::Success,
+            logs: vec![],
+            trace: None,
+            coverage: None,
+        },
+        TestResult {
+            name: String::from("FAILS"),
+            path: None,
+            return_data: Some(String::from("deadbeef")),
+            revert_reason: None,
+            counterexample: None,
+            divergence: None,
+            gas: 1337,
+            status: TestStatus::Revert,
+            logs: vec![],
+            trace: None,
+            coverage: None,
+        },
+    ]
+}
+
+#[test]
+fn test_junit_report() {
+    let report = gen_junit_report(&results(), Duration::from_millis(1500));
+    assert_eq!(
+        report,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="huffc" tests="2" failures="1" time="1.5000">
+  <testsuite name="huffc" tests="2" failures="1" time="1.5000">
+    <testcase name="PASSES" classname="huffc">
+      <properties>
+        <property name="gas" value="42"/>
+      </properties>
+    </testcase>
+    <testcase name="FAILS" classname="huffc">
+      <properties>
+        <property name="gas" value="1337"/>
+      </properties>
+      <failure message="Reverted with data: 0xdeadbeef" type="Revert"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+    );
+}
+
+#[test]
+fn test_tap_report() {
+    let report = gen_tap_report(&results());
+    assert_eq!(
+        report,
+        r#"TAP version 13
+1..2
+ok 1 - PASSES
+  ---
+  gas: 42
+  ...
+not ok 2 - FAILS
+  ---
+  gas: 1337
+  message: "Reverted with data: 0xdeadbeef"
+  ...
+"#
+    );
+    // Failure messages are escaped within their quoted scalar
+    let mut failing = results().remove(1);
+    failing.revert_reason = Some(String::from("Error(\"a \\ b\")"));
+    assert!(gen_tap_report(&[failing])
+        .contains("  message: \"Reverted with Error(\\\"a \\\\ b\\\")\"\n"));
+}
+
+#[test]
+fn test_write_test_report() {
+    let path = std::env::temp_dir().join(format!("huff-report-{}.xml", std::process::id()));
+    write_test_report(&results(), ReportKind::JUnit, Duration::default(), &path).unwrap();
+    let report = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(report.contains(r#"<failure message="Reverted with data: 0xdeadbeef" type="Revert"/>"#));
+
+    // Human readable reports can only be printed
+    assert!(gen_test_report(&results(), ReportKind::Table, Duration::default()).is_err());
+    assert_eq!(ReportKind::try_from(&Some(String::from("JUnit"))).unwrap(), ReportKind::JUnit);
+    assert_eq!(ReportKind::try_from(&Some(String::from("tap"))).unwrap(), ReportKind::TAP);
+    assert_eq!(ReportKind::try_from(&None).unwrap(), ReportKind::List);
+    assert!(ReportKind::try_from(&Some(String::from("xml"))).is_err());
+}
@@ -0,0 +1,59 @@
+use huff_tests::{
+    prelude::{TestFilter, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+#[test]
+fn test_setup_state_is_restored_per_test() {
+    let source = r#"
+#define macro SETUP() = takes (0) returns (0) {
+    0x01 0x00 sstore
+}
+
+#define macro ASSERT_SLOT_ONE() = takes (0) returns (0) {
+    0x00 sload 0x01 eq ok jumpi
+    0x00 dup1 revert
+    ok:
+}
+
+#define test OVERWRITES() = {
+    ASSERT_SLOT_ONE()
+    0x02 0x00 sstore
+}
+
+#define test READS() = {
+    ASSERT_SLOT_ONE()
+}
+"#;
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", source)]).grab_contracts().unwrap();
+    let results = HuffTester::new(&contracts[0], Rc::new(TestFilter::default())).execute().unwrap();
+
+    assert_eq!(results.len(), 2);
+    for result in results {
+        assert!(matches!(result.status, TestStatus::Success), "{} failed", result.name);
+    }
+}
+
+#[test]
+fn test_reverting_setup() {
+    let source = r#"
+#define macro SETUP() = takes (0) returns (0) {
+    0x00 dup1 revert
+}
+
+#define test NOOP() = {
+    0x01
+}
+"#;
+    let evm_version = EVMVersion::default();
+    let contracts =
+        common::compiler(&evm_version, &[("main.huff", source)]).grab_contracts().unwrap();
+    let err = HuffTester::new(&contracts[0], Rc::new(TestFilter::default())).execute().unwrap_err();
+    assert_eq!(err.0, "SETUP macro reverted");
+}
@@ -0,0 +1,104 @@
+use huff_tests::prelude::{
+    compare_snapshot, read_snapshot, write_snapshot, GasDelta, TestResult, TestStatus,
+};
+
+fn result(name: &str, gas: u64) -> TestResult {
+    TestResult {
+        name: name.to_string(),
+        path: Some(String::from("test.huff")),
+        return_data: None,
+        revert_reason: None,
+        counterexample: None,
+        divergence: None,
+        gas,
+        status: TestStatus::Success,
+        logs: vec![],
+        trace: None,
+        coverage: None,
+    }
+}
+
+#[test]
+fn test_snapshot_roundtrip() {
+    let path = std::env::temp_dir().join("huff_tests_snapshot_roundtrip.gas-snapshot");
+    let mut other = result("TEST_A", 300);
+    other.path = Some(String::from("other.huff"));
+    let results = vec![result("TEST_B", 200), result("TEST_A", 100), other];
+
+    // Tests of the same name in different files are kept apart
+    write_snapshot(&results, &path).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "other.huff::TEST_A (gas: 300)\ntest.huff::TEST_A (gas: 100)\ntest.huff::TEST_B (gas: 200)\n"
+            .to_string()
+    );
+
+    let snapshot = read_snapshot(&path).unwrap();
+    assert_eq!(snapshot.get("test.huff::TEST_A"), Some(&100));
+    assert_eq!(snapshot.get("test.huff::TEST_B"), Some(&200));
+    assert_eq!(snapshot.get("other.huff::TEST_A"), Some(&300));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_invalid_snapshot_entry() {
+    let path = std::env::temp_dir().join("huff_tests_invalid.gas-snapshot");
+    std::fs::write(&path, "TEST_A 100\n").unwrap();
+    assert!(read_snapshot(&path).is_err());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_compare_snapshot() {
+    let snapshot = vec![
+        (String::from("test.huff::CHEAPER"), 100),
+        (String::from("test.huff::FROM_ZERO"), 0),
+        (String::from("test.huff::PRICIER"), 100),
+        (String::from("test.huff::REMOVED"), 100),
+        (String::from("test.huff::WITHIN_TOLERANCE"), 1000),
+    ]
+    .into_iter()
+    .collect();
+    let results = vec![
+        result("CHEAPER", 90),
+        result("FROM_ZERO", 1),
+        result("PRICIER", 150),
+        result("WITHIN_TOLERANCE", 1005),
+        result("NEW", 10),
+    ];
+
+    let deltas = compare_snapshot(&results, &snapshot);
+    let delta = |name: &str, previous, current| GasDelta {
+        name: format!("test.huff::{name}"),
+        previous,
+        current,
+    };
+    assert_eq!(
+        deltas,
+        vec![
+            delta("CHEAPER", Some(100), Some(90)),
+            delta("FROM_ZERO", Some(0), Some(1)),
+            delta("NEW", None, Some(10)),
+            delta("PRICIER", Some(100), Some(150)),
+            delta("REMOVED", Some(100), None),
+            delta("WITHIN_TOLERANCE", Some(1000), Some(1005)),
+        ]
+    );
+
+    let regressions = |tolerance| {
+        deltas
+            .iter()
+            .filter(|d| d.is_regression(tolerance))
+            .map(|d| d.name.trim_start_matches("test.huff::"))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(regressions(0.0), vec!["FROM_ZERO", "PRICIER", "WITHIN_TOLERANCE"]);
+    assert_eq!(regressions(1.0), vec!["FROM_ZERO", "PRICIER"]);
+    assert_eq!(regressions(1000.0), vec!["FROM_ZERO"]);
+    assert_eq!(deltas[0].diff(), -10);
+    assert_eq!(deltas[3].percent(), 50.0);
+
+    // Removed tests have not changed
+    assert_eq!(deltas[4].diff(), 0);
+}
@@ -0,0 +1,57 @@
+use huff_tests::{
+    prelude::{TestFilter, TestStatus},
+    HuffTester,
+};
+use huff_utils::prelude::EVMVersion;
+use std::rc::Rc;
+
+mod common;
+
+#[test]
+fn test_trace_maps_failing_test_to_source() {
+    let main = r#"#include "./utils.huff"
+
+#define test FAILS() = {
+    0x01 0x00 mstore
+    FAIL()
+}
+
+#define test PASSES() = {
+    0x01
+}
+"#;
+    let utils = r#"
+#define macro FAIL() = takes (0) returns (0) {
+    0x00 0x00 revert
+}
+"#;
+
+    let evm_version = EVMVersion::default();
+    let contracts = common::compiler(&evm_version, &[("main.huff", main), ("utils.huff", utils)])
+        .grab_contracts()
+        .unwrap();
+
+    let mut tester = HuffTester::new(&contracts[0], Rc::new(TestFilter::default()));
+    tester.runner.verbosity = 3;
+    let results = tester.execute().unwrap();
+
+    // Traces are only kept for failing tests at this verbosity
+    let failing = results.iter().find(|r| r.name == "FAILS").unwrap();
+    let passing = results.iter().find(|r| r.name == "PASSES").unwrap();
+    assert!(matches!(failing.status, TestStatus::Revert));
+    assert!(passing.trace.is_none());
+
+    let trace = failing.trace.as_ref().unwrap();
+    let opcodes = trace.iter().map(|s| s.opcode.as_str()).collect::<Vec<_>>();
+    assert_eq!(opcodes, vec!["PUSH1", "PUSH0", "MSTORE", "PUSH0", "PUSH0", "REVERT"]);
+
+    // Memory writes and the stack top are recorded
+    assert_eq!(trace[2].memory_write, Some((0, format!("{:064x}", 1))));
+    assert_eq!(trace[1].stack_top, Some(String::from("0x0")));
+
+    // Steps are mapped back to the macro and file that generated them
+    assert_eq!(trace[2].macro_name, Some(String::from("FAILS")));
+    assert_eq!(trace[2].location, Some(String::from("main.huff:4")));
+    assert_eq!(trace[5].macro_name, Some(String::from("FAIL")));
+    assert_eq!(trace[5].location, Some(String::from("utils.huff:3")));
+}
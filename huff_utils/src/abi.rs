@@ -423,3 +423,106 @@ impl From<String> for FunctionParamType {
         FunctionParamType::convert_string_to_type(&string).unwrap()
     }
 }
+
+/// Canonicalizes a function, event or error signature into the form that is hashed for its
+/// selector, or returns `None` if it isn't a valid signature.
+///
+/// Whitespace, parameter names and `indexed` or data location keywords are dropped, and `uint`
+/// and `int` are expanded, so `transfer(address to, uint amount)` becomes
+/// `transfer(address,uint256)`.
+pub fn canonicalize_signature(signature: &str) -> Option<String> {
+    let (name, params) = signature.trim().split_once('(')?;
+    let name = name.trim();
+    if !is_identifier(name) {
+        return None
+    }
+    Some(format!("{name}({})", canonicalize_params(params.strip_suffix(')')?)?))
+}
+
+/// Canonicalizes a comma separated list of parameters
+fn canonicalize_params(params: &str) -> Option<String> {
+    if params.trim().is_empty() {
+        return Some(String::default())
+    }
+    let mut types = vec![];
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                types.push(canonicalize_param(&params[start..i])?);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return None
+    }
+    types.push(canonicalize_param(&params[start..])?);
+    Some(types.join(","))
+}
+
+/// Canonicalizes the type of a single parameter, dropping its name and keywords
+fn canonicalize_param(param: &str) -> Option<String> {
+    let param = param.trim();
+    let (mut ty, mut rest) = if let Some(inner) = param.strip_prefix('(') {
+        // The tuple ends at its matching parenthesis
+        let mut depth = 1usize;
+        let end = inner.char_indices().find_map(|(i, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })?;
+        (format!("({})", canonicalize_params(&inner[..end])?), &inner[end + 1..])
+    } else {
+        let end = param.find(|c: char| c.is_whitespace() || c == '[').unwrap_or(param.len());
+        (canonicalize_elementary_type(&param[..end])?, &param[end..])
+    };
+
+    // Array dimensions follow the type
+    while let Some(dimension) = rest.trim_start().strip_prefix('[') {
+        let (size, remaining) = dimension.split_once(']')?;
+        let size = size.trim();
+        if !size.is_empty() && size.parse::<usize>().ok().filter(|s| *s > 0).is_none() {
+            return None
+        }
+        ty.push_str(&format!("[{size}]"));
+        rest = remaining;
+    }
+
+    // Followed by an optional keyword and name
+    match rest.split_whitespace().collect::<Vec<_>>()[..] {
+        [] => Some(ty),
+        [name] if is_identifier(name) => Some(ty),
+        ["indexed" | "memory" | "calldata" | "storage", name] if is_identifier(name) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Canonicalizes an elementary type, such as `uint` or `bytes32`
+fn canonicalize_elementary_type(ty: &str) -> Option<String> {
+    let sized = |prefix: &str, valid: fn(usize) -> bool| {
+        ty.strip_prefix(prefix)
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|s| valid(*s))
+            .map(|s| format!("{prefix}{s}"))
+    };
+    match ty {
+        "address" | "bool" | "string" | "bytes" => Some(ty.to_string()),
+        "uint" | "int" => Some(format!("{ty}256")),
+        _ => sized("uint", |s| s % 8 == 0 && (8..=256).contains(&s))
+            .or_else(|| sized("int", |s| s % 8 == 0 && (8..=256).contains(&s)))
+            .or_else(|| sized("bytes", |s| (1..=32).contains(&s))),
+    }
+}
+
+/// Checks if a string is a valid identifier, which is the name of a signature or parameter
+fn is_identifier(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$') &&
+        s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::ops::Index;
 
 use crate::{
+    abi::canonicalize_signature,
     bytecode::*,
     bytes_util::*,
     error::CodegenError,
@@ -139,6 +140,110 @@ impl Contract {
         }
     }
 
+    /// Returns the labels each function selector is dispatched to, in the order they're
+    /// referenced.
+    ///
+    /// A dispatch is a `__FUNC_SIG` invocation or a literal selector followed by a label and a
+    /// `jumpi` in the body of any macro or label, as in `dup1 __FUNC_SIG(transfer) eq transfer
+    /// jumpi`. A `__DISPATCH` invocation dispatches every function to its label, as given by
+    /// [dispatch_targets](Contract::dispatch_targets).
+    pub fn dispatch_labels(&self) -> BTreeMap<[u8; 4], Vec<String>> {
+        let mut dispatches: BTreeMap<[u8; 4], Vec<String>> = BTreeMap::new();
+        // Bodies are popped in source order
+        let mut bodies = self.macros.iter().rev().map(|m| &m.statements).collect::<Vec<_>>();
+        while let Some(statements) = bodies.pop() {
+            let (mut selector, mut label) = (None, None);
+            let mut nested = vec![];
+            for s in statements {
+                match &s.ty {
+                    StatementType::BuiltinFunctionCall(bf)
+                        if bf.kind == BuiltinFunctionKind::Dispatch =>
+                    {
+                        for (s, l) in self.dispatch_targets(bf).unwrap_or_default() {
+                            let labels = dispatches.entry(s).or_default();
+                            if !labels.contains(&l) {
+                                labels.push(l);
+                            }
+                        }
+                    }
+                    StatementType::BuiltinFunctionCall(bf) => {
+                        if let Some(s) = self.builtin_selector(bf) {
+                            (selector, label) = (Some(s), None);
+                        }
+                    }
+                    StatementType::Literal(l) => {
+                        let s = self
+                            .functions
+                            .iter()
+                            .map(|f| f.signature)
+                            .find(|s| l[..28].iter().all(|b| *b == 0) && l[28..] == s[..]);
+                        if s.is_some() {
+                            (selector, label) = (s, None);
+                        }
+                    }
+                    StatementType::LabelCall(l) if selector.is_some() => label = Some(l.clone()),
+                    StatementType::Opcode(Opcode::Jumpi) => {
+                        if let (Some(s), Some(l)) = (selector.take(), label.take()) {
+                            let labels = dispatches.entry(s).or_default();
+                            if !labels.contains(&l) {
+                                labels.push(l);
+                            }
+                        }
+                    }
+                    StatementType::Label(l) => nested.push(&l.inner),
+                    _ => {}
+                }
+            }
+            bodies.extend(nested.into_iter().rev());
+        }
+        dispatches
+    }
+
+    /// Returns the selector and label of each function a `__DISPATCH` invocation dispatches to, in
+    /// definition order.
+    ///
+    /// Functions are dispatched to the label named after them, unless a jump table is passed as the
+    /// second argument, in which case they're dispatched to the label at their position in the
+    /// table. Returns `None` if the table is missing, or if it doesn't hold a label per function.
+    pub fn dispatch_targets(&self, bf: &BuiltinFunctionCall) -> Option<Vec<([u8; 4], String)>> {
+        let labels = match bf.args.get(1) {
+            Some(arg) => {
+                let table = self.find_table_by_name(arg.name.as_ref()?)?;
+                table
+                    .statements
+                    .into_iter()
+                    .map(|s| match s.ty {
+                        StatementType::LabelCall(l) => Some(l),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?
+            }
+            None => self.functions.iter().map(|f| f.name.clone()).collect(),
+        };
+        if labels.len() != self.functions.len() {
+            return None
+        }
+        Some(self.functions.iter().map(|f| f.signature).zip(labels).collect())
+    }
+
+    /// Returns the selector pushed by a `__FUNC_SIG` invocation, given the name of a function or
+    /// error, or a raw signature
+    fn builtin_selector(&self, bf: &BuiltinFunctionCall) -> Option<[u8; 4]> {
+        if bf.kind != BuiltinFunctionKind::FunctionSignature {
+            return None
+        }
+        let name = bf.args.first()?.name.as_ref()?;
+        if let Some(f) = self.functions.iter().find(|f| &f.name == name) {
+            return Some(f.signature)
+        }
+        if let Some(e) = self.errors.iter().find(|e| &e.name == name) {
+            return Some(e.selector)
+        }
+        let mut selector = [0u8; 4];
+        hash_bytes(&mut selector, &canonicalize_signature(name)?);
+        Some(selector)
+    }
+
     /// Derives the FreeStoragePointers into their bytes32 representation
     pub fn derive_storage_pointers(&mut self) {
         let mut storage_pointers: Vec<(String, [u8; 32])> = Vec::new();
@@ -448,6 +553,13 @@ pub struct FunctionDefinition {
     pub span: AstSpan,
 }
 
+impl FunctionDefinition {
+    /// The canonical signature of the function, which is hashed for its selector
+    pub fn canonical_signature(&self) -> String {
+        canonical_signature(&self.name, &self.inputs)
+    }
+}
+
 /// Function Types
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum FunctionType {
@@ -461,6 +573,17 @@ pub enum FunctionType {
     Pure,
 }
 
+impl Display for FunctionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FunctionType::View => write!(f, "view"),
+            FunctionType::Payable => write!(f, "payable"),
+            FunctionType::NonPayable => write!(f, "nonpayable"),
+            FunctionType::Pure => write!(f, "pure"),
+        }
+    }
+}
+
 impl FunctionType {
     /// Get the string representation of the function type for usage in Solidity interface
     /// generation.
@@ -486,6 +609,13 @@ pub struct EventDefinition {
     pub hash: Literal,
 }
 
+impl EventDefinition {
+    /// The canonical signature of the event, which is hashed for its topic
+    pub fn canonical_signature(&self) -> String {
+        canonical_signature(&self.name, &self.parameters)
+    }
+}
+
 /// A Table Definition
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TableDefinition {
@@ -765,6 +895,19 @@ pub struct ErrorDefinition {
     pub span: AstSpan,
 }
 
+impl ErrorDefinition {
+    /// The canonical signature of the error, which is hashed for its selector
+    pub fn canonical_signature(&self) -> String {
+        canonical_signature(&self.name, &self.parameters)
+    }
+}
+
+/// The canonical signature of a definition from its name and parameters
+fn canonical_signature(name: &str, parameters: &[Argument]) -> String {
+    let types = parameters.iter().filter_map(|p| p.arg_type.clone()).collect::<Vec<_>>();
+    format!("{name}({})", types.join(","))
+}
+
 /// A Jump Destination
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Label {
@@ -810,6 +953,14 @@ pub enum BuiltinFunctionKind {
     DynConstructorArg,
     /// Inject Raw Bytes
     Verbatim,
+    /// Push the bytes of a string
+    Bytes,
+    /// Assert the current bytecode offset
+    AssertPc,
+    /// Assert the current stack depth
+    AssertStack,
+    /// Generate a function selector dispatcher
+    Dispatch,
 }
 
 impl From<String> for BuiltinFunctionKind {
@@ -824,6 +975,10 @@ impl From<String> for BuiltinFunctionKind {
             "__RIGHTPAD" => BuiltinFunctionKind::RightPad,
             "__CODECOPY_DYN_ARG" => BuiltinFunctionKind::DynConstructorArg,
             "__VERBATIM" => BuiltinFunctionKind::Verbatim,
+            "__BYTES" => BuiltinFunctionKind::Bytes,
+            "__ASSERT_PC" => BuiltinFunctionKind::AssertPc,
+            "__ASSERT_STACK" => BuiltinFunctionKind::AssertStack,
+            "__DISPATCH" => BuiltinFunctionKind::Dispatch,
             _ => panic!("Invalid Builtin Function Kind"), /* This should never be reached,
                                                            * builtins are validated with a
                                                            * `try_from` call in the lexer. */
@@ -845,6 +1000,10 @@ impl TryFrom<&String> for BuiltinFunctionKind {
             "__RIGHTPAD" => Ok(BuiltinFunctionKind::RightPad),
             "__CODECOPY_DYN_ARG" => Ok(BuiltinFunctionKind::DynConstructorArg),
             "__VERBATIM" => Ok(BuiltinFunctionKind::Verbatim),
+            "__BYTES" => Ok(BuiltinFunctionKind::Bytes),
+            "__ASSERT_PC" => Ok(BuiltinFunctionKind::AssertPc),
+            "__ASSERT_STACK" => Ok(BuiltinFunctionKind::AssertStack),
+            "__DISPATCH" => Ok(BuiltinFunctionKind::Dispatch),
             _ => Err(()),
         }
     }
@@ -918,8 +1077,69 @@ pub struct Decorator {
 pub enum DecoratorFlag {
     /// Sets the calldata of the test call transaction
     Calldata(String),
+    /// Sets the calldata of the test call transaction to an ABI encoded function call, given
+    /// the function's signature or name and its arguments
+    CalldataAbi(String, Vec<String>),
+    /// Compiles and deploys another contract, given its path relative to the test's file and
+    /// its constructor arguments, and pushes its address onto the stack before the test runs
+    Deploy(String, Vec<String>),
     /// Sets the value of the test call transaction
     Value(Literal),
+    /// Sets the caller of the test call transaction
+    Caller(Literal),
+    /// Sets the origin of the test call transaction
+    Origin(Literal),
+    /// Sets the block timestamp
+    Timestamp(Literal),
+    /// Sets the block number
+    Number(Literal),
+    /// Sets the chain id
+    ChainId(Literal),
+    /// Sets the block base fee
+    BaseFee(Literal),
+    /// Sets the gas limit of the test call transaction
+    GasLimit(Literal),
+    /// Sets the block coinbase
+    Coinbase(Literal),
+    /// Sets the block prevrandao
+    PrevRandao(Literal),
+    /// Marks a test as an invariant, checked after each call of an invariant testing campaign
+    Invariant,
+}
+
+impl DecoratorFlag {
+    /// The maximum number of bytes a flag's literal argument may occupy, if it is restricted
+    /// to fewer than 32 bytes.
+    pub fn max_literal_bytes(&self) -> Option<usize> {
+        match self {
+            DecoratorFlag::Caller(_) | DecoratorFlag::Origin(_) | DecoratorFlag::Coinbase(_) => {
+                Some(20)
+            }
+            DecoratorFlag::ChainId(_) | DecoratorFlag::GasLimit(_) => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Create a flag of the same kind with the given literal argument. Returns `None` for
+    /// flags that do not accept a literal.
+    pub fn with_literal(&self, literal: Literal) -> Option<Self> {
+        match self {
+            DecoratorFlag::Calldata(_) |
+            DecoratorFlag::CalldataAbi(_, _) |
+            DecoratorFlag::Deploy(_, _) |
+            DecoratorFlag::Invariant => None,
+            DecoratorFlag::Value(_) => Some(DecoratorFlag::Value(literal)),
+            DecoratorFlag::Caller(_) => Some(DecoratorFlag::Caller(literal)),
+            DecoratorFlag::Origin(_) => Some(DecoratorFlag::Origin(literal)),
+            DecoratorFlag::Timestamp(_) => Some(DecoratorFlag::Timestamp(literal)),
+            DecoratorFlag::Number(_) => Some(DecoratorFlag::Number(literal)),
+            DecoratorFlag::ChainId(_) => Some(DecoratorFlag::ChainId(literal)),
+            DecoratorFlag::BaseFee(_) => Some(DecoratorFlag::BaseFee(literal)),
+            DecoratorFlag::GasLimit(_) => Some(DecoratorFlag::GasLimit(literal)),
+            DecoratorFlag::Coinbase(_) => Some(DecoratorFlag::Coinbase(literal)),
+            DecoratorFlag::PrevRandao(_) => Some(DecoratorFlag::PrevRandao(literal)),
+        }
+    }
 }
 
 impl TryFrom<&String> for DecoratorFlag {
@@ -928,7 +1148,19 @@ impl TryFrom<&String> for DecoratorFlag {
     fn try_from(value: &String) -> Result<Self, Self::Error> {
         match value.as_str() {
             "calldata" => Ok(DecoratorFlag::Calldata(String::default())),
+            "calldata_abi" => Ok(DecoratorFlag::CalldataAbi(String::default(), Vec::default())),
+            "deploy" => Ok(DecoratorFlag::Deploy(String::default(), Vec::default())),
             "value" => Ok(DecoratorFlag::Value(Literal::default())),
+            "caller" => Ok(DecoratorFlag::Caller(Literal::default())),
+            "origin" => Ok(DecoratorFlag::Origin(Literal::default())),
+            "timestamp" => Ok(DecoratorFlag::Timestamp(Literal::default())),
+            "number" => Ok(DecoratorFlag::Number(Literal::default())),
+            "chainid" => Ok(DecoratorFlag::ChainId(Literal::default())),
+            "basefee" => Ok(DecoratorFlag::BaseFee(Literal::default())),
+            "gas_limit" => Ok(DecoratorFlag::GasLimit(Literal::default())),
+            "coinbase" => Ok(DecoratorFlag::Coinbase(Literal::default())),
+            "prevrandao" => Ok(DecoratorFlag::PrevRandao(Literal::default())),
+            "invariant" => Ok(DecoratorFlag::Invariant),
             _ => Err(()),
         }
     }
@@ -81,6 +81,10 @@ pub struct BytecodeRes {
     pub table_instances: Jumps,
     /// Utilized Tables
     pub utilized_tables: Vec<TableDefinition>,
+    /// Source Map
+    pub source_map: SourceMap,
+    /// Failed PC assertions as (expected, found, span), reported once the bytecode is placed
+    pub failed_pc_assertions: Vec<(usize, usize, AstSpan)>,
 }
 
 impl Display for BytecodeRes {
@@ -118,6 +122,26 @@ pub type Jumps = Vec<Jump>;
 /// Type to map `Jump` labels to their bytecode indices
 pub type LabelIndices = BTreeMap<String, usize>;
 
+/// A Source Map Entry
+///
+/// Maps a bytecode offset to the macro and span it was generated from.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceMapEntry {
+    /// Bytecode offset of the generated bytes
+    pub pc: usize,
+    /// Name of the macro the bytes were generated within
+    pub macro_name: String,
+    /// Name of the label block the bytes were generated within, if any
+    pub label: Option<String>,
+    /// The span of the statement that generated the bytes
+    pub span: AstSpan,
+    /// Whether the macro the bytes were generated within is a test
+    pub test: bool,
+}
+
+/// Type for a vec of `SourceMapEntry`s, ordered by bytecode offset
+pub type SourceMap = Vec<SourceMapEntry>;
+
 /// Typw to map circular_codesize labels to their bytecode indices
 pub type CircularCodeSizeIndices = BTreeSet<(String, usize)>;
 
@@ -1,4 +1,5 @@
-use crate::{evm::Opcode, evm_version::EVMVersion};
+use crate::{error::LexicalErrorKind, evm::Opcode, evm_version::EVMVersion};
+use ethers_core::types::U256;
 use std::num::ParseIntError;
 use tiny_keccak::{Hasher, Keccak};
 
@@ -90,3 +91,69 @@ fn format_push0(evm_version: &EVMVersion, hex_literal: String) -> String {
 pub fn format_literal(hex_literal: String) -> String {
     format!("{:02x}{hex_literal}", 95 + hex_literal.len() / 2)
 }
+
+/// The units a decimal literal may be suffixed with, and their number of decimals
+pub const UNITS: [(&str, usize); 3] = [("wei", 0), ("gwei", 9), ("ether", 18)];
+
+/// Convert a decimal literal to a `[u8; 32]`.
+///
+/// The literal may separate its digits with underscores, be written in scientific notation, and
+/// be followed by a unit, i.e. `1_000_000`, `1e18` or `1.5 ether`. Its value must be an integer.
+pub fn decimal_to_bytes32(literal: &str) -> Result<[u8; 32], LexicalErrorKind> {
+    let invalid = || LexicalErrorKind::InvalidNumber(literal.to_string());
+    let (number, decimals) = match literal.split_once(' ') {
+        Some((number, unit)) => {
+            let (_, decimals) = UNITS.iter().find(|(u, _)| *u == unit).ok_or_else(invalid)?;
+            (number, *decimals)
+        }
+        None => (literal, 0),
+    };
+
+    // Underscores may only separate digits
+    let digits = |part: &str| -> Result<String, LexicalErrorKind> {
+        let valid = part.starts_with(|c: char| c.is_ascii_digit()) &&
+            part.ends_with(|c: char| c.is_ascii_digit()) &&
+            part.chars().all(|c| c.is_ascii_digit() || c == '_') &&
+            !part.contains("__");
+        if valid {
+            Ok(part.replace('_', ""))
+        } else {
+            Err(invalid())
+        }
+    };
+    let (mantissa, exponent) = match number.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => {
+            (mantissa, digits(exponent)?.parse::<usize>().map_err(|_| invalid())?)
+        }
+        None => (number, 0),
+    };
+    let (integer, fraction) = match mantissa.split_once('.') {
+        Some((integer, fraction)) => (digits(integer)?, digits(fraction)?),
+        None => (digits(mantissa)?, String::new()),
+    };
+
+    // The fractional digits must be shifted into the integer by the exponent and unit
+    let overflow = || LexicalErrorKind::NumberOverflow(literal.to_string());
+    let shift = exponent.checked_add(decimals).ok_or_else(overflow)?;
+    let mut significand = format!("{integer}{fraction}");
+    if fraction.len() > shift {
+        let truncated = significand.len() - (fraction.len() - shift);
+        if significand[truncated..].chars().any(|c| c != '0') {
+            return Err(invalid())
+        }
+        significand.truncate(truncated);
+    }
+    let significand = U256::from_dec_str(&significand).map_err(|_| overflow())?;
+    let value = if significand.is_zero() {
+        significand
+    } else {
+        U256::from(10)
+            .checked_pow(U256::from(shift.saturating_sub(fraction.len())))
+            .and_then(|scale| significand.checked_mul(scale))
+            .ok_or_else(overflow)?
+    };
+
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    Ok(bytes)
+}
@@ -67,6 +67,22 @@ pub enum ParserErrorKind {
     InvalidDecoratorFlagArg(TokenKind),
     /// Duplicate MACRO
     DuplicateMacro(String),
+    /// Duplicate CONSTANT
+    DuplicateConstant(String),
+    /// Duplicate TABLE
+    DuplicateTable(String),
+    /// Duplicate FUNCTION signature
+    DuplicateFunction(String),
+    /// Duplicate EVENT signature
+    DuplicateEvent(String),
+    /// Duplicate ERROR signature
+    DuplicateError(String),
+    /// A conditional directive without a matching "#ifdef" or "#ifndef"
+    UnmatchedConditional(TokenKind),
+    /// A conditional directive without a matching "#endif"
+    UnterminatedConditional(TokenKind),
+    /// Two different signatures with the same selector, as (previous, current)
+    SelectorCollision(String, String),
 }
 
 /// A Lexing Error
@@ -97,6 +113,10 @@ pub enum LexicalErrorKind {
     InvalidArraySize(String),
     /// Invalid Primitive EVM Type
     InvalidPrimitiveType(String),
+    /// Invalid Decimal Number
+    InvalidNumber(String),
+    /// A Number that doesn't fit in 32 bytes
+    NumberOverflow(String),
 }
 
 impl Spanned for LexicalError {
@@ -116,6 +136,10 @@ impl<W: Write> Report<W> for LexicalError {
             LexicalErrorKind::InvalidPrimitiveType(str) => {
                 write!(f.out, "Invalid Primitive EVM Type '{str}'")
             }
+            LexicalErrorKind::InvalidNumber(str) => write!(f.out, "Invalid number '{str}'"),
+            LexicalErrorKind::NumberOverflow(str) => {
+                write!(f.out, "Number '{str}' is larger than 32 bytes")
+            }
         }
     }
 }
@@ -183,6 +207,17 @@ pub enum CodegenErrorKind {
     TestInvocation(String),
     /// Incorrect dynamic argument index
     InvalidDynArgIndex,
+    /// A string literal longer than 32 bytes
+    BytesOverflow(String),
+    /// The bytecode offset differs from the asserted offset, as (expected, actual)
+    FailedPcAssertion(usize, usize),
+    /// The stack depth differs from the asserted depth, as (expected, actual)
+    FailedStackAssertion(usize, isize),
+    /// A malformed signature passed to a builtin
+    InvalidSignature(String),
+    /// A signature passed to a builtin that matches no definition, as (builtin, signature), which
+    /// is only a warning
+    UndefinedSignature(String, String),
 }
 
 impl Spanned for CodegenError {
@@ -247,6 +282,19 @@ impl<W: Write> Report<W> for CodegenError {
             CodegenErrorKind::InvalidDynArgIndex => {
                 write!(f.out, "Invalid Dynamic Constructor Argument Index")
             }
+            CodegenErrorKind::BytesOverflow(s) => {
+                write!(f.out, "String literal \"{s}\" is longer than 32 bytes")
+            }
+            CodegenErrorKind::FailedPcAssertion(expected, actual) => {
+                write!(f.out, "Expected the bytecode offset to be {expected:#x}, found {actual:#x}")
+            }
+            CodegenErrorKind::FailedStackAssertion(expected, actual) => {
+                write!(f.out, "Expected a stack depth of {expected}, found {actual}")
+            }
+            CodegenErrorKind::InvalidSignature(sig) => write!(f.out, "Invalid signature \"{sig}\""),
+            CodegenErrorKind::UndefinedSignature(builtin, sig) => {
+                write!(f.out, "Signature \"{sig}\" passed to {builtin} matches no definition")
+            }
         }
     }
 }
@@ -266,6 +314,8 @@ pub enum CompilerError {
     CodegenError(CodegenError),
     /// Multiple Failed Compiles
     FailedCompiles(Vec<CompilerError>),
+    /// A file includes itself, through the chain of included files
+    IncludeCycle(Vec<String>),
 }
 
 impl fmt::Display for CompilerError {
@@ -307,6 +357,24 @@ impl fmt::Display for CompilerError {
                         le.span.source_seg()
                     )
                 }
+                LexicalErrorKind::InvalidNumber(n) => {
+                    write!(
+                        f,
+                        "\nError: Invalid Number: \"{}\" {}{}\n",
+                        n,
+                        le.span.identifier(),
+                        le.span.source_seg()
+                    )
+                }
+                LexicalErrorKind::NumberOverflow(n) => {
+                    write!(
+                        f,
+                        "\nError: Number Larger Than 32 Bytes: \"{}\" {}{}\n",
+                        n,
+                        le.span.identifier(),
+                        le.span.source_seg()
+                    )
+                }
             },
             CompilerError::FileUnpackError(ue) => match ue {
                 UnpackError::InvalidDirectory(id) => {
@@ -521,6 +589,73 @@ impl fmt::Display for CompilerError {
                         pe.spans.error(pe.hint.as_ref())
                     )
                 }
+                ParserErrorKind::DuplicateConstant(name) => {
+                    write!(
+                        f,
+                        "\nError: Duplicate CONSTANT name found: \"{}\" \n{}\n",
+                        name,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
+                ParserErrorKind::DuplicateTable(name) => {
+                    write!(
+                        f,
+                        "\nError: Duplicate TABLE name found: \"{}\" \n{}\n",
+                        name,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
+                ParserErrorKind::DuplicateFunction(name) => {
+                    write!(
+                        f,
+                        "\nError: Duplicate FUNCTION signature found: \"{}\" \n{}\n",
+                        name,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
+                ParserErrorKind::DuplicateEvent(name) => {
+                    write!(
+                        f,
+                        "\nError: Duplicate EVENT signature found: \"{}\" \n{}\n",
+                        name,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
+                ParserErrorKind::DuplicateError(name) => {
+                    write!(
+                        f,
+                        "\nError: Duplicate ERROR signature found: \"{}\" \n{}\n",
+                        name,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
+                ParserErrorKind::UnmatchedConditional(directive) => {
+                    write!(
+                        f,
+                        "\nError at token {}: Unmatched Conditional Directive: \"{}\" \n{}\n",
+                        pe.cursor,
+                        directive,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
+                ParserErrorKind::UnterminatedConditional(directive) => {
+                    write!(
+                        f,
+                        "\nError at token {}: Unterminated Conditional Directive: \"{}\" \n{}\n",
+                        pe.cursor,
+                        directive,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
+                ParserErrorKind::SelectorCollision(previous, current) => {
+                    write!(
+                        f,
+                        "\nError: Selector collision between \"{}\" and \"{}\" \n{}\n",
+                        previous,
+                        current,
+                        pe.spans.error(pe.hint.as_ref())
+                    )
+                }
             },
             CompilerError::PathBufRead(os_str) => {
                 write!(
@@ -622,6 +757,37 @@ impl fmt::Display for CompilerError {
                         ce.span.error(None)
                     )
                 }
+                CodegenErrorKind::BytesOverflow(s) => {
+                    write!(
+                        f,
+                        "\nError: String literal \"{s}\" is longer than 32 bytes\n{}\n",
+                        ce.span.error(None)
+                    )
+                }
+                CodegenErrorKind::FailedPcAssertion(expected, actual) => {
+                    write!(
+                        f,
+                        "\nError: Expected the bytecode offset to be {expected:#x}, found {actual:#x}\n{}\n",
+                        ce.span.error(None)
+                    )
+                }
+                CodegenErrorKind::FailedStackAssertion(expected, actual) => {
+                    write!(
+                        f,
+                        "\nError: Expected a stack depth of {expected}, found {actual}\n{}\n",
+                        ce.span.error(None)
+                    )
+                }
+                CodegenErrorKind::InvalidSignature(sig) => {
+                    write!(f, "\nError: Invalid signature \"{sig}\"\n{}\n", ce.span.error(None))
+                }
+                CodegenErrorKind::UndefinedSignature(builtin, sig) => {
+                    write!(
+                        f,
+                        "\nWarning: Signature \"{sig}\" passed to {builtin} matches no definition\n{}\n",
+                        ce.span.error(None)
+                    )
+                }
             },
             CompilerError::FailedCompiles(v) => {
                 v.iter().for_each(|ce| {
@@ -629,6 +795,9 @@ impl fmt::Display for CompilerError {
                 });
                 Ok(())
             }
+            CompilerError::IncludeCycle(chain) => {
+                write!(f, "\nError: Include Cycle: {}\n", chain.join(" -> "))
+            }
         }
     }
 }
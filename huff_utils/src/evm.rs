@@ -818,6 +818,49 @@ impl Opcode {
         )
     }
 
+    /// Returns the number of stack items the opcode consumes and produces
+    pub fn stack_io(&self) -> (usize, usize) {
+        let code = u8::from_str_radix(&self.string(), 16).unwrap_or_default();
+        match self {
+            Opcode::Stop | Opcode::Jumpdest | Opcode::Invalid => (0, 0),
+            Opcode::Iszero |
+            Opcode::Not |
+            Opcode::Balance |
+            Opcode::Calldataload |
+            Opcode::Extcodesize |
+            Opcode::Extcodehash |
+            Opcode::Blockhash |
+            Opcode::Blobhash |
+            Opcode::Mload |
+            Opcode::Sload |
+            Opcode::Tload => (1, 1),
+            Opcode::Addmod | Opcode::Mulmod | Opcode::Create => (3, 1),
+            Opcode::Create2 => (4, 1),
+            Opcode::Call | Opcode::Callcode => (7, 1),
+            Opcode::Delegatecall | Opcode::Staticcall => (6, 1),
+            Opcode::Calldatacopy | Opcode::Codecopy | Opcode::Returndatacopy | Opcode::Mcopy => {
+                (3, 0)
+            }
+            Opcode::Extcodecopy => (4, 0),
+            Opcode::Pop | Opcode::Jump | Opcode::Selfdestruct => (1, 0),
+            Opcode::Mstore |
+            Opcode::Mstore8 |
+            Opcode::Sstore |
+            Opcode::Tstore |
+            Opcode::Jumpi |
+            Opcode::Return |
+            Opcode::Revert => (2, 0),
+            // Arithmetic, comparison, bitwise and hashing operations
+            _ if code < 0x30 => (2, 1),
+            // Dups, swaps and logs
+            _ if (0x80..0x90).contains(&code) => ((code - 0x7f) as usize, (code - 0x7e) as usize),
+            _ if (0x90..0xa0).contains(&code) => ((code - 0x8e) as usize, (code - 0x8e) as usize),
+            _ if (0xa0..0xa5).contains(&code) => ((code - 0x9e) as usize, 0),
+            // Environment and block information, and pushes
+            _ => (0, 1),
+        }
+    }
+
     /// Prefixes the literal if necessary
     pub fn prefix_push_literal(&self, literal: &str) -> String {
         if self.is_value_push() {
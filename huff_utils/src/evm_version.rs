@@ -4,7 +4,7 @@ use std::cmp::PartialOrd;
 ///
 /// Determines which features will be available when compiling.
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum SupportedEVMVersions {
     /// Introduced prevrandao, disallow difficulty opcode (does not affect codegen)
     Paris,
@@ -12,7 +12,7 @@ pub enum SupportedEVMVersions {
     Shanghai,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// EVM Version
 pub struct EVMVersion {
     version: SupportedEVMVersions,
@@ -24,6 +24,11 @@ impl EVMVersion {
         Self { version }
     }
 
+    /// The selected EVM version
+    pub fn version(&self) -> SupportedEVMVersions {
+        self.version
+    }
+
     /// As PartialOrd is implemented in the struct, all versions after shanghai will support this
     pub fn has_push0(&self) -> bool {
         self.version >= SupportedEVMVersions::Shanghai
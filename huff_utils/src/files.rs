@@ -1,7 +1,6 @@
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::{
-    cell::Ref,
     collections::HashMap,
     fs,
     io::{BufReader, Read},
@@ -28,18 +27,19 @@ pub struct FullFileSource<'a> {
 
 impl<'a> FullFileSource<'a> {
     /// Get the relative span
-    pub fn relative_span(&self, span: Ref<'a, Span>) -> Option<Span> {
+    ///
+    /// Maps a span over the flattened source to a span relative to the file it originated from.
+    /// If the span is contained within multiple files, the innermost file is used.
+    pub fn relative_span(&self, span: &Span) -> Option<Span> {
         self.spans
             .iter()
-            .filter(|s| s.1.start < span.start && s.1.end > span.end)
+            .filter(|s| s.1.start <= span.start && s.1.end >= span.end)
+            .min_by_key(|s| s.1.end - s.1.start)
             .map(|s| Span {
                 start: span.start - s.1.start,
                 end: span.end - s.1.start,
                 file: Some(s.0.clone()),
             })
-            .collect::<Vec<Span>>()
-            .into_iter()
-            .next()
     }
 }
 
@@ -232,11 +232,16 @@ impl FileSource {
         match &self_ref.dependencies {
             Some(vfs) => {
                 for fs in vfs {
-                    let mut flattened = FileSource::fully_flatten(Arc::clone(fs));
-                    let span =
-                        Span::new(full_source.len()..(full_source.len() + flattened.0.len()), None);
+                    let flattened = FileSource::fully_flatten(Arc::clone(fs));
+                    let offset = full_source.len();
+                    let span = Span::new(offset..(offset + flattened.0.len()), None);
                     full_source.push_str(&flattened.0);
-                    relative_positions.append(&mut flattened.1);
+                    // Shift the dependency's relative positions into the flattened source
+                    relative_positions.extend(
+                        flattened.1.into_iter().map(|(f, s)| {
+                            (f, Span::new((s.start + offset)..(s.end + offset), None))
+                        }),
+                    );
                     relative_positions.push((Arc::clone(fs), span))
                 }
             }
@@ -247,6 +252,44 @@ impl FileSource {
         (full_source, relative_positions)
     }
 
+    /// Copies a `FileSource` along with its dependencies, so that the copy is a distinct file
+    pub fn deep_copy(self_ref: &Arc<FileSource>) -> Arc<FileSource> {
+        Arc::new(FileSource {
+            id: self_ref.id,
+            path: self_ref.path.clone(),
+            source: self_ref.source.clone(),
+            access: self_ref.access,
+            dependencies: self_ref
+                .dependencies
+                .as_ref()
+                .map(|deps| deps.iter().map(FileSource::deep_copy).collect()),
+        })
+    }
+
+    /// Canonicalizes a File Path, so that different paths to the same file are equal.
+    ///
+    /// Paths to files that exist are resolved by the file system, and other paths, such as those
+    /// of in-memory files, by removing their `.` and `..` components.
+    pub fn canonical_path(path: &str) -> String {
+        if let Some(canonical) =
+            std::fs::canonicalize(path).ok().and_then(|p| p.to_str().map(String::from))
+        {
+            return canonical
+        }
+        let mut components: Vec<&str> = vec![];
+        for component in path.split('/') {
+            match component {
+                "" | "." => {}
+                ".." if components.last().is_some_and(|c| *c != "..") => {
+                    components.pop();
+                }
+                component => components.push(component),
+            }
+        }
+        let prefix = if path.starts_with('/') { "/" } else { "" };
+        format!("{prefix}{}", components.join("/"))
+    }
+
     /// Derives a File Path's directory
     pub fn derive_dir(path: &str) -> Option<String> {
         let path = PathBuf::from(path);
@@ -351,6 +394,26 @@ impl Span {
             .unwrap_or_default()
     }
 
+    /// Produces the line number of the span's start within its file, if the file is known
+    pub fn line(&self) -> Option<usize> {
+        self.file.as_ref().and_then(|f| f.source.as_ref()).and_then(|s| {
+            s.get(0..self.start).map(|prefix| prefix.bytes().filter(|&c| c == b'\n').count() + 1)
+        })
+    }
+
+    /// Produces a `path:line` location string, if the file is known
+    pub fn location(&self) -> Option<String> {
+        self.line().map(|line| format!("{}:{line}", self.file.as_ref().unwrap().path))
+    }
+
+    /// Produces the full source line containing the span's start, if the file is known
+    pub fn source_line(&self) -> Option<String> {
+        let source = self.file.as_ref().and_then(|f| f.source.as_ref())?;
+        let line_start = source.get(0..self.start)?.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[self.start..].find('\n').map_or(source.len(), |i| self.start + i);
+        Some(source[line_start..line_end].to_string())
+    }
+
     /// Produces a source segment string
     pub fn source_seg(&self) -> String {
         self.file
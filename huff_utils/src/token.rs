@@ -33,6 +33,14 @@ pub enum TokenKind {
     Define,
     /// "#include" keyword
     Include,
+    /// "#ifdef" directive
+    IfDef,
+    /// "#ifndef" directive
+    IfNDef,
+    /// "#else" directive
+    Else,
+    /// "#endif" directive
+    EndIf,
     /// "macro" keyword
     Macro,
     /// "fn" keyword
@@ -151,6 +159,10 @@ impl fmt::Display for TokenKind {
             TokenKind::Div => "/",
             TokenKind::Define => "#define",
             TokenKind::Include => "#include",
+            TokenKind::IfDef => "#ifdef",
+            TokenKind::IfNDef => "#ifndef",
+            TokenKind::Else => "#else",
+            TokenKind::EndIf => "#endif",
             TokenKind::Macro => "macro",
             TokenKind::Fn => "fn",
             TokenKind::Test => "test",
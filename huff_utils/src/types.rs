@@ -1,5 +1,5 @@
 use crate::bytes_util::*;
-use ethers_core::abi::{ethereum_types::*, token::*, Tokenizable};
+use ethers_core::abi::{ethereum_types::*, token::*, ParamType, Tokenizable};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::{fmt, str::FromStr};
@@ -115,6 +115,9 @@ impl TryFrom<String> for EToken {
         if input.starts_with("0x") {
             // remove 0x prefix
             let cleaned_input = input.get(2..input.len()).unwrap();
+            if !cleaned_input.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Invalid hex input: {input}"))
+            }
             // either address or fixed bytes
             if cleaned_input.len() <= 64 {
                 // could be either address or fixed bytes
@@ -173,3 +176,57 @@ impl TryFrom<String> for EToken {
         }
     }
 }
+
+impl EToken {
+    /// Convert an input string to a token of the given ABI type.
+    ///
+    /// Hex inputs shorter than an address are parsed as left padded 32 byte words, so they are
+    /// narrowed to the requested type here.
+    pub fn try_from_param_type(input: String, kind: &ParamType) -> Result<Self, String> {
+        let invalid = || format!("Invalid input for {kind}: {input}");
+        let token = match kind {
+            ParamType::String => Token::String(input.clone()),
+            ParamType::Bytes => Token::Bytes(
+                str_to_vec(input.strip_prefix("0x").unwrap_or(&input))
+                    .map_err(|e| e.to_string())?,
+            ),
+            ParamType::Array(inner) | ParamType::FixedArray(inner, _) => {
+                let tokens = input
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(',')
+                    .map(|x| x.replace([' ', '"', '\''], ""))
+                    .filter(|x| !x.is_empty())
+                    .map(|x| EToken::try_from_param_type(x, inner).map(|t| t.0))
+                    .collect::<Result<Vec<Token>, _>>()?;
+                match kind {
+                    ParamType::FixedArray(_, size) if tokens.len() != *size => {
+                        return Err(invalid())
+                    }
+                    ParamType::FixedArray(_, _) => Token::FixedArray(tokens),
+                    _ => Token::Array(tokens),
+                }
+            }
+            _ => match (kind, EToken::try_from(input.clone())?.0) {
+                (ParamType::Address, Token::Address(a)) => Token::Address(a),
+                (ParamType::Address, Token::FixedBytes(b)) if b[..12].iter().all(|b| *b == 0) => {
+                    Token::Address(H160::from_slice(&b[12..]))
+                }
+                (ParamType::Uint(_), Token::Uint(u)) => Token::Uint(u),
+                (ParamType::Uint(_), Token::FixedBytes(b)) => {
+                    Token::Uint(U256::from_big_endian(&b))
+                }
+                (ParamType::Int(_), Token::Int(i) | Token::Uint(i)) => Token::Int(i),
+                (ParamType::Int(_), Token::FixedBytes(b)) => Token::Int(U256::from_big_endian(&b)),
+                (ParamType::Bool, Token::Bool(b)) => Token::Bool(b),
+                (ParamType::FixedBytes(size), Token::FixedBytes(b))
+                    if *size <= 32 && b[..32 - size].iter().all(|b| *b == 0) =>
+                {
+                    Token::FixedBytes(b[32 - size..].to_vec())
+                }
+                _ => return Err(invalid()),
+            },
+        };
+        Ok(EToken(token))
+    }
+}
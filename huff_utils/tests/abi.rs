@@ -77,3 +77,42 @@ fn test_display_func_param_type() {
         }
     }
 }
+
+#[test]
+fn test_canonicalize_signature() {
+    let signatures = [
+        ("transfer(address,uint256)", "transfer(address,uint256)"),
+        ("transfer(address to, uint amount)", "transfer(address,uint256)"),
+        (" transfer ( address , int ) ", "transfer(address,int256)"),
+        (
+            "Transfer(address indexed from, address indexed, uint)",
+            "Transfer(address,address,uint256)",
+        ),
+        ("multicall(bytes[] calldata data)", "multicall(bytes[])"),
+        (
+            "swap((address,uint)[2][] memory orders, bytes32)",
+            "swap((address,uint256)[2][],bytes32)",
+        ),
+        ("nested(((uint),bool) data)", "nested(((uint256),bool))"),
+        ("totalSupply()", "totalSupply()"),
+    ];
+    for (signature, canonical) in signatures {
+        assert_eq!(canonicalize_signature(signature), Some(canonical.to_string()));
+    }
+
+    let invalid = [
+        "transfer",
+        "transfer(address,uint256",
+        "transfer(adress,uint256)",
+        "transfer(address,uint7)",
+        "transfer(bytes33)",
+        "transfer(uint256[0])",
+        "transfer(address,,uint256)",
+        "transfer((address,uint256)",
+        "transfer(address to from)",
+        "1transfer(address)",
+    ];
+    for signature in invalid {
+        assert_eq!(canonicalize_signature(signature), None, "{signature}");
+    }
+}
@@ -18,3 +18,26 @@ fn test_hex_to_usize() {
         assert_eq!(converted_usize, i);
     }
 }
+
+#[test]
+fn test_decimal_to_bytes32() {
+    let values = [
+        ("0", "00"),
+        ("255", "ff"),
+        ("1_000_000", "0f4240"),
+        ("1e18", "0de0b6b3a7640000"),
+        ("2.5E3", "09c4"),
+        ("0.1 ether", "016345785d8a0000"),
+        ("30 gwei", "06fc23ac00"),
+        ("7 wei", "07"),
+        ("1.0", "01"),
+    ];
+    for (decimal, hex) in values {
+        assert_eq!(decimal_to_bytes32(decimal).unwrap(), str_to_bytes32(hex), "{decimal}");
+    }
+
+    // The largest 32 byte value
+    let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    assert_eq!(decimal_to_bytes32(max).unwrap(), [0xff; 32]);
+    assert!(decimal_to_bytes32(&max.replace("935", "936")).is_err());
+}
@@ -61,6 +61,24 @@ fn test_source_seg() {
     );
 }
 
+#[test]
+fn test_source_line() {
+    let source = "#define macro MAIN() = takes (0) returns (0) {\n    0x01 0x00 mstore // []\n}";
+    let file = Arc::new(files::FileSource {
+        id: uuid::Uuid::nil(),
+        path: "./main.huff".to_string(),
+        source: Some(source.to_string()),
+        access: None,
+        dependencies: None,
+    });
+
+    let span = Span { start: 57, end: 63, file: Some(Arc::clone(&file)) };
+    assert_eq!(span.source_line().unwrap(), "    0x01 0x00 mstore // []");
+    let span = Span { start: 0, end: 7, file: Some(file) };
+    assert_eq!(span.source_line().unwrap(), "#define macro MAIN() = takes (0) returns (0) {");
+    assert_eq!(Span::EOF.source_line(), None);
+}
+
 #[test]
 fn test_derive_dir() {
     let localized = files::FileSource::derive_dir("./examples/ERC20.huff").unwrap();
@@ -126,3 +144,19 @@ fn test_localize_file() {
             .unwrap();
     assert_eq!(localized, "./random_dir/Address.huff");
 }
+
+#[test]
+fn test_canonical_path() {
+    let canonical = files::FileSource::canonical_path("./examples/../utils/./Address.huff");
+    assert_eq!(canonical, "utils/Address.huff");
+    let canonical = files::FileSource::canonical_path("utils//Address.huff");
+    assert_eq!(canonical, "utils/Address.huff");
+    let canonical = files::FileSource::canonical_path("../../examples/../Address.huff");
+    assert_eq!(canonical, "../../Address.huff");
+    let canonical = files::FileSource::canonical_path("/examples/./Address.huff");
+    assert_eq!(canonical, "/examples/Address.huff");
+
+    // Paths to files that exist are resolved by the file system
+    let canonical = files::FileSource::canonical_path("./tests/../tests/remappings.txt");
+    assert_eq!(canonical, std::fs::canonicalize("tests/remappings.txt").unwrap().to_str().unwrap());
+}